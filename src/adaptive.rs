@@ -0,0 +1,189 @@
+use crate::reminder::{ReminderOutcome, ReminderType};
+use crate::shared_state::BlinkSettings;
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Answers looked at before adjusting an interval.
+const WINDOW: usize = 6;
+/// Skips and snoozes within [`WINDOW`] that mean the interval is too short.
+const DISMISSALS_TO_LENGTHEN: usize = 4;
+/// How much a too-short interval grows, in percent.
+const GROWTH: u64 = 25;
+
+/// Bounds for adaptive blink and posture intervals, in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveSettings {
+    pub enabled: bool,
+    pub blink_min: u64,
+    pub blink_max: u64,
+    pub posture_min: u64,
+    pub posture_max: u64,
+}
+
+impl Default for AdaptiveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            blink_min: 20,
+            blink_max: 120,
+            posture_min: 60,
+            posture_max: 1800,
+        }
+    }
+}
+
+impl AdaptiveSettings {
+    /// Bounds for `kind`, or `None` if its interval isn't adapted.
+    fn bounds(&self, kind: ReminderType) -> Option<(u64, u64)> {
+        match kind {
+            ReminderType::Blink => Some((self.blink_min, self.blink_max)),
+            ReminderType::Posture => Some((self.posture_min, self.posture_max)),
+            _ => None,
+        }
+    }
+}
+
+/// A change made to an interval, with the reason shown in the settings window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntervalAdjustment {
+    pub kind: ReminderType,
+    pub at: NaiveDateTime,
+    /// Old and new interval, in seconds.
+    pub from: u64,
+    pub to: u64,
+    pub reason: String,
+}
+
+/// The `kind` interval before the first adjustment in `log`, or `None` if
+/// it was never adjusted.
+pub fn interval_before(log: &[IntervalAdjustment], kind: ReminderType) -> Option<u64> {
+    log.iter()
+        .find(|adjustment| adjustment.kind == kind)
+        .map(|adjustment| adjustment.from)
+}
+
+/// Recent answers per reminder type, used to lengthen intervals the user
+/// keeps brushing off.
+///
+/// Only explicit answers count: a blink popup closing on its own is how it
+/// is meant to be used, not a sign that it comes too often.
+#[derive(Debug, Default)]
+pub struct AdaptiveIntervals {
+    history: HashMap<ReminderType, VecDeque<ReminderOutcome>>,
+}
+
+impl AdaptiveIntervals {
+    /// Note how the user answered a `kind` reminder. Returns the new interval
+    /// when the recent answers call for one.
+    pub fn record(
+        &mut self,
+        kind: ReminderType,
+        outcome: ReminderOutcome,
+        settings: &BlinkSettings,
+    ) -> Option<IntervalAdjustment> {
+        let adaptive = &settings.adaptive;
+        let (min, max) = adaptive.bounds(kind).filter(|_| adaptive.enabled)?;
+        if outcome == ReminderOutcome::Ignored {
+            return None;
+        }
+        let history = self.history.entry(kind).or_default();
+        history.push_back(outcome);
+        if history.len() > WINDOW {
+            history.pop_front();
+        }
+        let dismissed = history
+            .iter()
+            .filter(|outcome| {
+                matches!(outcome, ReminderOutcome::Skipped | ReminderOutcome::Snoozed)
+            })
+            .count();
+        if dismissed < DISMISSALS_TO_LENGTHEN {
+            return None;
+        }
+
+        let from = settings.reminder_definition(kind)?.interval;
+        let max = max.max(min);
+        if from >= max {
+            return None;
+        }
+        let to = (from + from * GROWTH / 100).clamp(min, max).max(from + 1);
+        // Start collecting evidence afresh for the new interval
+        history.clear();
+        let name = match kind {
+            ReminderType::Blink => "blink",
+            _ => "posture",
+        };
+        Some(IntervalAdjustment {
+            kind,
+            at: Local::now().naive_local(),
+            from,
+            to,
+            reason: format!(
+                "You skipped or snoozed {dismissed} of the last {WINDOW} {name} reminders."
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adjustment(kind: ReminderType, from: u64, to: u64) -> IntervalAdjustment {
+        IntervalAdjustment {
+            kind,
+            at: NaiveDateTime::default(),
+            from,
+            to,
+            reason: String::new(),
+        }
+    }
+
+    #[test]
+    fn reset_goes_back_to_the_interval_before_the_first_adjustment() {
+        let log = [
+            adjustment(ReminderType::Posture, 60, 75),
+            adjustment(ReminderType::Blink, 20, 25),
+            adjustment(ReminderType::Posture, 75, 93),
+        ];
+        assert_eq!(interval_before(&log, ReminderType::Posture), Some(60));
+        assert_eq!(interval_before(&log, ReminderType::Blink), Some(20));
+        assert_eq!(interval_before(&log, ReminderType::EyeBreak), None);
+    }
+
+    #[test]
+    fn repeated_dismissals_lengthen_the_interval_once() {
+        let settings = BlinkSettings {
+            posture_interval: 100,
+            adaptive: AdaptiveSettings {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut adaptive = AdaptiveIntervals::default();
+        let outcomes = [
+            ReminderOutcome::Skipped,
+            ReminderOutcome::Done,
+            ReminderOutcome::Snoozed,
+            ReminderOutcome::Skipped,
+        ];
+        for outcome in outcomes {
+            assert_eq!(
+                adaptive.record(ReminderType::Posture, outcome, &settings),
+                None
+            );
+        }
+        let adjustment = adaptive
+            .record(ReminderType::Posture, ReminderOutcome::Skipped, &settings)
+            .unwrap();
+        assert_eq!((adjustment.from, adjustment.to), (100, 125));
+        // The evidence starts over at the new interval
+        assert_eq!(
+            adaptive.record(ReminderType::Posture, ReminderOutcome::Skipped, &settings),
+            None
+        );
+    }
+}
@@ -0,0 +1,936 @@
+use chrono::{
+    Datelike, Local, Months, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use ical::parser::ical::component::IcalEvent;
+use ical::property::Property;
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// How often to look for changes to the calendar files.
+pub const CALENDAR_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Most recurrence periods expanded for a single event, so a broken rule
+/// can't spin forever.
+const MAX_PERIODS: u32 = 50_000;
+
+/// `TZID`s already warned about, so a calendar that is re-read every time
+/// it changes doesn't repeat the warning.
+static UNKNOWN_ZONES: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Busy events read from one or more `.ics` files, in local time.
+///
+/// Times with a `TZID` and UTC times are converted. Windows zone names, as
+/// written by Outlook and Exchange, are mapped to their IANA zone; events
+/// in any other zone chrono-tz doesn't know are skipped with a warning.
+/// All-day, transparent and cancelled events never count as busy.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Calendar {
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Event {
+    /// In `zone`, which the rule is expanded in too.
+    start: NaiveDateTime,
+    zone: Zone,
+    length: TimeDelta,
+    rule: Option<Rule>,
+    /// Local start of occurrences removed by `EXDATE` or replaced by a
+    /// `RECURRENCE-ID` override.
+    exceptions: Vec<NaiveDateTime>,
+    /// Days in `zone` removed by a date-only `EXDATE`.
+    excluded_days: Vec<NaiveDate>,
+}
+
+/// The time zone an event's times are written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    /// Floating times, without a `TZID`.
+    Local,
+    Named(Tz),
+}
+
+/// The supported subset of an `RRULE`.
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDateTime>,
+    /// `BYDAY` entries, with the ordinal used by monthly rules (e.g. `2TU`, `-1FR`).
+    by_day: Vec<(Option<i32>, Weekday)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Calendar {
+    /// Parse every `VEVENT` in an iCalendar document.
+    pub fn parse(ics: impl std::io::BufRead) -> Result<Self, ical::parser::ParserError> {
+        let mut parsed = Vec::new();
+        for calendar in ical::IcalParser::new(ics) {
+            parsed.extend(calendar?.events);
+        }
+        // Overridden occurrences show up as separate events with a RECURRENCE-ID
+        let overrides: Vec<(String, NaiveDateTime)> = parsed
+            .iter()
+            .filter_map(|event| {
+                let uid = property(event, "UID")?.value.clone()?;
+                Some((uid, date_time(property(event, "RECURRENCE-ID")?)?))
+            })
+            .collect();
+        let events = parsed
+            .iter()
+            .filter_map(|ical| {
+                let mut event = Event::from_ical(ical)?;
+                if event.rule.is_some() {
+                    let uid = property(ical, "UID").and_then(|uid| uid.value.as_deref());
+                    event.exceptions.extend(
+                        overrides
+                            .iter()
+                            .filter(|(id, _)| Some(id.as_str()) == uid)
+                            .map(|(_, at)| *at),
+                    );
+                }
+                Some(event)
+            })
+            .collect();
+        Ok(Self { events })
+    }
+
+    /// Merge the events of several calendars.
+    pub fn merge(calendars: impl IntoIterator<Item = Calendar>) -> Self {
+        Self {
+            events: calendars
+                .into_iter()
+                .flat_map(|calendar| calendar.events)
+                .collect(),
+        }
+    }
+
+    /// When the meeting in progress at `at` ends, or `None` if `at` is free.
+    pub fn busy_until(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.events
+            .iter()
+            .flat_map(|event| {
+                event
+                    .occurrences(at, at)
+                    .into_iter()
+                    .map(|start| start + event.length)
+            })
+            .max()
+    }
+
+    /// The first meeting start or end after `after`, looking one day ahead.
+    pub fn next_change(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let horizon = after + TimeDelta::days(1);
+        self.events
+            .iter()
+            .flat_map(|event| {
+                event
+                    .occurrences(after, horizon)
+                    .into_iter()
+                    .flat_map(|start| [start, start + event.length])
+            })
+            .filter(|change| *change > after)
+            .min()
+    }
+}
+
+impl Event {
+    fn from_ical(ical: &IcalEvent) -> Option<Self> {
+        let value = |name| property(ical, name).and_then(|prop| prop.value.as_deref());
+        if value("TRANSP") == Some("TRANSPARENT") || value("STATUS") == Some("CANCELLED") {
+            return None;
+        }
+        let dtstart = property(ical, "DTSTART")?;
+        let zone = Zone::of(dtstart)?;
+        let start = parse_date_time(dtstart.value.as_deref()?, zone)?;
+        let local_start = zone.to_local(start)?;
+        let end = match property(ical, "DTEND") {
+            Some(end) => date_time(end)?,
+            None => local_start + parse_duration(value("DURATION")?)?,
+        };
+        if end <= local_start {
+            return None;
+        }
+        // An override is a single occurrence, even if it repeats the RRULE
+        let rule = match property(ical, "RECURRENCE-ID") {
+            Some(_) => None,
+            None => value("RRULE").and_then(|rrule| Rule::parse(rrule, zone)),
+        };
+        let mut exceptions = Vec::new();
+        let mut excluded_days = Vec::new();
+        for prop in ical.properties.iter().filter(|prop| prop.name == "EXDATE") {
+            let Some(exdate_zone) = Zone::of(prop) else {
+                continue;
+            };
+            for value in prop.value.iter().flat_map(|value| value.split(',')) {
+                match parse_date(value) {
+                    Some(day) => excluded_days.push(day),
+                    None => exceptions.extend(
+                        parse_date_time(value, exdate_zone).and_then(|at| exdate_zone.to_local(at)),
+                    ),
+                }
+            }
+        }
+        Some(Self {
+            start,
+            zone,
+            length: end - local_start,
+            rule,
+            exceptions,
+            excluded_days,
+        })
+    }
+
+    /// Start times of the occurrences that overlap `from..=to`.
+    fn occurrences(&self, from: NaiveDateTime, to: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let overlaps = |start: &NaiveDateTime| *start <= to && *start + self.length > from;
+        let Some(rule) = &self.rule else {
+            return self
+                .zone
+                .to_local(self.start)
+                .filter(overlaps)
+                .into_iter()
+                .collect();
+        };
+        // `from` and `to` are local, so widen them by the most two zones can differ
+        let margin = TimeDelta::days(2);
+        rule.starts(self.start, from - self.length - margin, to + margin)
+            .into_iter()
+            .filter(|start| !self.excluded_days.contains(&start.date()))
+            .filter_map(|start| self.zone.to_local(start))
+            .filter(|start| !self.exceptions.contains(start))
+            .filter(overlaps)
+            .collect()
+    }
+}
+
+impl Zone {
+    /// The zone named by the `TZID` parameter of `prop`, an IANA or a
+    /// Windows zone name, or `None` if it is neither.
+    fn of(prop: &Property) -> Option<Self> {
+        let tzid = prop
+            .params
+            .iter()
+            .flatten()
+            .find(|(name, _)| name == "TZID")
+            .and_then(|(_, values)| values.first());
+        let Some(tzid) = tzid.map(|tzid| tzid.trim_matches('"')) else {
+            return Some(Zone::Local);
+        };
+        let tz = tzid.parse().ok().or_else(|| {
+            let (_, iana) = WINDOWS_ZONES.iter().find(|(windows, _)| *windows == tzid)?;
+            iana.parse().ok()
+        });
+        if tz.is_none() && UNKNOWN_ZONES.lock().unwrap().insert(tzid.to_owned()) {
+            eprintln!("Warning: skipping calendar events in unknown time zone {tzid:?}");
+        }
+        tz.map(Zone::Named)
+    }
+
+    /// `at` in this zone as local time, or `None` if a clock change skips it.
+    fn to_local(self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self {
+            Zone::Local => Some(at),
+            Zone::Named(tz) => Some(
+                tz.from_local_datetime(&at)
+                    .earliest()?
+                    .with_timezone(&Local)
+                    .naive_local(),
+            ),
+        }
+    }
+
+    /// The UTC time `at` in this zone.
+    fn convert_utc(self, at: NaiveDateTime) -> NaiveDateTime {
+        let at = Utc.from_utc_datetime(&at);
+        match self {
+            Zone::Local => at.with_timezone(&Local).naive_local(),
+            Zone::Named(tz) => at.with_timezone(&tz).naive_local(),
+        }
+    }
+}
+
+impl Rule {
+    /// Parse `rrule` for an event in `zone`, which a UTC `UNTIL` is converted to.
+    fn parse(rrule: &str, zone: Zone) -> Option<Self> {
+        let mut rule = Rule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+        };
+        let mut frequency = None;
+        for part in rrule.split(';') {
+            let (key, value) = part.split_once('=')?;
+            match key {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        // Sub-daily meetings are not worth expanding
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => rule.interval = value.parse().ok().filter(|n| *n > 0)?,
+                "COUNT" => rule.count = value.parse().ok(),
+                "UNTIL" => {
+                    // A date-only UNTIL includes that whole day
+                    rule.until = parse_date_time(value, zone)
+                        .or_else(|| parse_date(value)?.and_hms_opt(23, 59, 59));
+                }
+                "BYDAY" => {
+                    rule.by_day = value.split(',').filter_map(parse_by_day).collect();
+                }
+                _ => {}
+            }
+        }
+        rule.frequency = frequency?;
+        Some(rule)
+    }
+
+    /// Occurrence start times from `first` on, skipping periods that end
+    /// before `from` where that doesn't change the `COUNT`, up to `to`.
+    fn starts(
+        &self,
+        first: NaiveDateTime,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> Vec<NaiveDateTime> {
+        let last = self.until.map_or(to, |until| until.min(to));
+        // Jump straight to the periods near `from` when nothing is being counted
+        let skip = match (self.count, self.frequency) {
+            (None, Frequency::Daily) => (from - first).num_days() / i64::from(self.interval) - 1,
+            (None, Frequency::Weekly) => (from - first).num_weeks() / i64::from(self.interval) - 1,
+            _ => 0,
+        };
+        let mut starts = Vec::new();
+        let mut emitted = 0;
+        for period in u32::try_from(skip.max(0)).unwrap_or(0)..MAX_PERIODS {
+            let Some(candidates) = self.period(first, period) else {
+                break;
+            };
+            for start in candidates.into_iter().filter(|start| *start >= first) {
+                if start > last || self.count.is_some_and(|count| emitted >= count) {
+                    return starts;
+                }
+                emitted += 1;
+                starts.push(start);
+            }
+        }
+        starts
+    }
+
+    /// Candidate start times in the `period`-th period after `first`, or
+    /// `None` once the dates run out.
+    fn period(&self, first: NaiveDateTime, period: u32) -> Option<Vec<NaiveDateTime>> {
+        let step = period.checked_mul(self.interval)?;
+        let time = first.time();
+        let date = first.date();
+        let mut dates = match self.frequency {
+            Frequency::Daily => vec![date.checked_add_days(chrono::Days::new(step.into()))?],
+            Frequency::Weekly => {
+                let monday = date.week(Weekday::Mon).first_day();
+                let week = monday.checked_add_days(chrono::Days::new(u64::from(step) * 7))?;
+                let days = if self.by_day.is_empty() {
+                    vec![date.weekday()]
+                } else {
+                    self.by_day.iter().map(|(_, day)| *day).collect()
+                };
+                days.into_iter()
+                    .filter_map(|day| {
+                        week.checked_add_days(chrono::Days::new(day.num_days_from_monday().into()))
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let month = date.with_day(1)?.checked_add_months(Months::new(step))?;
+                if self.by_day.is_empty() {
+                    // Months without this day (e.g. the 31st) are skipped
+                    month.with_day(date.day()).into_iter().collect()
+                } else {
+                    self.by_day
+                        .iter()
+                        .filter_map(|(ordinal, day)| nth_weekday(month, ordinal.unwrap_or(1), *day))
+                        .collect()
+                }
+            }
+            Frequency::Yearly => {
+                let year = date.year().checked_add(i32::try_from(step).ok()?)?;
+                NaiveDate::from_ymd_opt(year, date.month(), date.day())
+                    .into_iter()
+                    .collect()
+            }
+        };
+        dates.sort();
+        Some(dates.into_iter().map(|date| date.and_time(time)).collect())
+    }
+}
+
+/// The `n`-th `day` of the month starting at `month` (negative counts from the end).
+fn nth_weekday(month: NaiveDate, n: i32, day: Weekday) -> Option<NaiveDate> {
+    if n > 0 {
+        NaiveDate::from_weekday_of_month_opt(
+            month.year(),
+            month.month(),
+            day,
+            u8::try_from(n).ok()?,
+        )
+    } else {
+        let next_month = month.checked_add_months(Months::new(1))?;
+        let last = next_month.pred_opt()?;
+        let back = (7 + last.weekday().num_days_from_monday() - day.num_days_from_monday()) % 7;
+        let weeks = u64::try_from(-n - 1).ok()?;
+        let date = last.checked_sub_days(chrono::Days::new(u64::from(back) + 7 * weeks))?;
+        (date.month() == month.month()).then_some(date)
+    }
+}
+
+/// Parse a `BYDAY` entry such as `MO`, `2TU` or `-1FR`.
+fn parse_by_day(entry: &str) -> Option<(Option<i32>, Weekday)> {
+    let split = entry.len().checked_sub(2)?;
+    let day = match &entry[split..] {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+    let ordinal = match &entry[..split] {
+        "" => None,
+        ordinal => Some(ordinal.trim_start_matches('+').parse().ok()?),
+    };
+    Some((ordinal, day))
+}
+
+/// Windows time zone names and the IANA zone each stands for, after the
+/// CLDR `windowsZones` table.
+const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("UTC-11", "Etc/GMT+11"),
+    ("Aleutian Standard Time", "America/Adak"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Marquesas Standard Time", "Pacific/Marquesas"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("UTC-09", "Etc/GMT+9"),
+    ("Pacific Standard Time (Mexico)", "America/Tijuana"),
+    ("UTC-08", "Etc/GMT+8"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Mountain Standard Time (Mexico)", "America/Mazatlan"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("Yukon Standard Time", "America/Whitehorse"),
+    ("Central America Standard Time", "America/Guatemala"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Easter Island Standard Time", "Pacific/Easter"),
+    ("Central Standard Time (Mexico)", "America/Mexico_City"),
+    ("Canada Central Standard Time", "America/Regina"),
+    ("SA Pacific Standard Time", "America/Bogota"),
+    ("Eastern Standard Time (Mexico)", "America/Cancun"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("Haiti Standard Time", "America/Port-au-Prince"),
+    ("Cuba Standard Time", "America/Havana"),
+    ("US Eastern Standard Time", "America/Indiana/Indianapolis"),
+    ("Turks And Caicos Standard Time", "America/Grand_Turk"),
+    ("Paraguay Standard Time", "America/Asuncion"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("Venezuela Standard Time", "America/Caracas"),
+    ("Central Brazilian Standard Time", "America/Cuiaba"),
+    ("SA Western Standard Time", "America/La_Paz"),
+    ("Pacific SA Standard Time", "America/Santiago"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("Tocantins Standard Time", "America/Araguaina"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("SA Eastern Standard Time", "America/Cayenne"),
+    ("Argentina Standard Time", "America/Argentina/Buenos_Aires"),
+    ("Greenland Standard Time", "America/Nuuk"),
+    ("Montevideo Standard Time", "America/Montevideo"),
+    ("Magallanes Standard Time", "America/Punta_Arenas"),
+    ("Saint Pierre Standard Time", "America/Miquelon"),
+    ("Bahia Standard Time", "America/Bahia"),
+    ("UTC-02", "Etc/GMT+2"),
+    ("Azores Standard Time", "Atlantic/Azores"),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde"),
+    ("UTC", "Etc/UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("Sao Tome Standard Time", "Africa/Sao_Tome"),
+    ("Morocco Standard Time", "Africa/Casablanca"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("W. Central Africa Standard Time", "Africa/Lagos"),
+    ("Jordan Standard Time", "Asia/Amman"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Middle East Standard Time", "Asia/Beirut"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("Syria Standard Time", "Asia/Damascus"),
+    ("West Bank Standard Time", "Asia/Hebron"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("FLE Standard Time", "Europe/Kyiv"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("South Sudan Standard Time", "Africa/Juba"),
+    ("Kaliningrad Standard Time", "Europe/Kaliningrad"),
+    ("Sudan Standard Time", "Africa/Khartoum"),
+    ("Libya Standard Time", "Africa/Tripoli"),
+    ("Namibia Standard Time", "Africa/Windhoek"),
+    ("Arabic Standard Time", "Asia/Baghdad"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Arab Standard Time", "Asia/Riyadh"),
+    ("Belarus Standard Time", "Europe/Minsk"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("E. Africa Standard Time", "Africa/Nairobi"),
+    ("Volgograd Standard Time", "Europe/Volgograd"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Astrakhan Standard Time", "Europe/Astrakhan"),
+    ("Azerbaijan Standard Time", "Asia/Baku"),
+    ("Russia Time Zone 3", "Europe/Samara"),
+    ("Mauritius Standard Time", "Indian/Mauritius"),
+    ("Saratov Standard Time", "Europe/Saratov"),
+    ("Georgian Standard Time", "Asia/Tbilisi"),
+    ("Caucasus Standard Time", "Asia/Yerevan"),
+    ("Afghanistan Standard Time", "Asia/Kabul"),
+    ("West Asia Standard Time", "Asia/Tashkent"),
+    ("Ekaterinburg Standard Time", "Asia/Yekaterinburg"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("Qyzylorda Standard Time", "Asia/Qyzylorda"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("Sri Lanka Standard Time", "Asia/Colombo"),
+    ("Nepal Standard Time", "Asia/Kathmandu"),
+    ("Central Asia Standard Time", "Asia/Bishkek"),
+    ("Bangladesh Standard Time", "Asia/Dhaka"),
+    ("Omsk Standard Time", "Asia/Omsk"),
+    ("Myanmar Standard Time", "Asia/Yangon"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("Altai Standard Time", "Asia/Barnaul"),
+    ("W. Mongolia Standard Time", "Asia/Hovd"),
+    ("North Asia Standard Time", "Asia/Krasnoyarsk"),
+    ("N. Central Asia Standard Time", "Asia/Novosibirsk"),
+    ("Tomsk Standard Time", "Asia/Tomsk"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("North Asia East Standard Time", "Asia/Irkutsk"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Ulaanbaatar Standard Time", "Asia/Ulaanbaatar"),
+    ("Aus Central W. Standard Time", "Australia/Eucla"),
+    ("Transbaikal Standard Time", "Asia/Chita"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("North Korea Standard Time", "Asia/Pyongyang"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("Yakutsk Standard Time", "Asia/Yakutsk"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide"),
+    ("AUS Central Standard Time", "Australia/Darwin"),
+    ("E. Australia Standard Time", "Australia/Brisbane"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("West Pacific Standard Time", "Pacific/Port_Moresby"),
+    ("Tasmania Standard Time", "Australia/Hobart"),
+    ("Vladivostok Standard Time", "Asia/Vladivostok"),
+    ("Lord Howe Standard Time", "Australia/Lord_Howe"),
+    ("Bougainville Standard Time", "Pacific/Bougainville"),
+    ("Russia Time Zone 10", "Asia/Srednekolymsk"),
+    ("Magadan Standard Time", "Asia/Magadan"),
+    ("Norfolk Standard Time", "Pacific/Norfolk"),
+    ("Sakhalin Standard Time", "Asia/Sakhalin"),
+    ("Central Pacific Standard Time", "Pacific/Guadalcanal"),
+    ("Russia Time Zone 11", "Asia/Kamchatka"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+    ("UTC+12", "Etc/GMT-12"),
+    ("Fiji Standard Time", "Pacific/Fiji"),
+    ("Chatham Islands Standard Time", "Pacific/Chatham"),
+    ("UTC+13", "Etc/GMT-13"),
+    ("Tonga Standard Time", "Pacific/Tongatapu"),
+    ("Samoa Standard Time", "Pacific/Apia"),
+    ("Line Islands Standard Time", "Pacific/Kiritimati"),
+];
+
+fn property<'a>(event: &'a IcalEvent, name: &str) -> Option<&'a Property> {
+    event.properties.iter().find(|prop| prop.name == name)
+}
+
+/// A date-time property in local time. All-day (`VALUE=DATE`) values are
+/// not meetings and give `None`, like times in an unknown zone.
+fn date_time(prop: &Property) -> Option<NaiveDateTime> {
+    let zone = Zone::of(prop)?;
+    zone.to_local(parse_date_time(prop.value.as_deref()?, zone)?)
+}
+
+/// Parse `20261018T100000Z` (UTC) or `20261018T100000` (already in `zone`),
+/// as a time in `zone`.
+fn parse_date_time(value: &str, zone: Zone) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    match value.strip_suffix('Z') {
+        Some(utc) => {
+            Some(zone.convert_utc(NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?))
+        }
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok(),
+    }
+}
+
+/// Parse a date-only value such as `20261018`.
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y%m%d").ok()
+}
+
+/// Parse an iCalendar duration such as `PT45M`, `PT1H30M` or `P1D`.
+fn parse_duration(value: &str) -> Option<TimeDelta> {
+    let value = value.trim().trim_start_matches('+').strip_prefix('P')?;
+    let (days, time) = value.split_once('T').unwrap_or((value, ""));
+    let mut total = TimeDelta::zero();
+    let mut number = String::new();
+    for (part, is_time) in [(days, false), (time, true)] {
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let n: i64 = number.parse().ok()?;
+            number.clear();
+            total += match (c, is_time) {
+                ('W', false) => TimeDelta::weeks(n),
+                ('D', false) => TimeDelta::days(n),
+                ('H', true) => TimeDelta::hours(n),
+                ('M', true) => TimeDelta::minutes(n),
+                ('S', true) => TimeDelta::seconds(n),
+                _ => return None,
+            };
+        }
+    }
+    Some(total)
+}
+
+/// Keeps the configured `.ics` files loaded, re-reading them when the list of
+/// files or any file's modification time changes.
+#[derive(Debug, Default)]
+pub struct CalendarFiles {
+    loaded: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl CalendarFiles {
+    /// The merged calendar if anything changed since the last call, otherwise
+    /// `None`. Files that can't be read or parsed are skipped with a warning.
+    pub fn refresh(&mut self, paths: &[PathBuf]) -> Option<Calendar> {
+        let current: Vec<(PathBuf, Option<SystemTime>)> = paths
+            .iter()
+            .map(|path| (path.clone(), modified(path)))
+            .collect();
+        if current == self.loaded {
+            return None;
+        }
+        self.loaded = current;
+        Some(Calendar::merge(paths.iter().filter_map(|path| {
+            match File::open(path)
+                .map_err(|err| err.to_string())
+                .and_then(|file| {
+                    Calendar::parse(BufReader::new(file)).map_err(|err| err.to_string())
+                }) {
+                Ok(calendar) => Some(calendar),
+                Err(err) => {
+                    eprintln!("Skipping calendar {}: {err}", path.display());
+                    None
+                }
+            }
+        })))
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+
+    /// A weekly meeting in New York with the second week off, a meeting in
+    /// a Windows zone name, a UTC one and one in a zone nobody knows.
+    const FIXTURE: &str = "\
+BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:standup\r
+DTSTART;TZID=America/New_York:20261005T090000\r
+DTEND;TZID=America/New_York:20261005T093000\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+EXDATE;VALUE=DATE:20261012\r
+SUMMARY:Standup\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:windows\r
+DTSTART;TZID=Eastern Standard Time:20261006T140000\r
+DTEND;TZID=Eastern Standard Time:20261006T150000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:utc\r
+DTSTART:20261007T120000Z\r
+DTEND:20261007T130000Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:mars\r
+DTSTART;TZID=Mars/Olympus_Mons:20261008T100000\r
+DTEND;TZID=Mars/Olympus_Mons:20261008T110000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn new_york(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        New_York
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Local)
+            .naive_local()
+    }
+
+    fn utc(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        Utc.with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Local)
+            .naive_local()
+    }
+
+    #[test]
+    fn events_are_read_in_their_own_time_zone() {
+        let calendar = Calendar::parse(FIXTURE.as_bytes()).unwrap();
+        assert_eq!(
+            calendar.busy_until(new_york(5, 9, 10)),
+            Some(new_york(5, 9, 30))
+        );
+        assert_eq!(calendar.busy_until(new_york(5, 9, 40)), None);
+        assert_eq!(calendar.busy_until(utc(7, 12, 30)), Some(utc(7, 13, 0)));
+    }
+
+    #[test]
+    fn windows_zone_names_are_mapped_to_iana_zones() {
+        let calendar = Calendar::parse(FIXTURE.as_bytes()).unwrap();
+        assert_eq!(
+            calendar.busy_until(new_york(6, 14, 30)),
+            Some(new_york(6, 15, 0))
+        );
+        for (windows, iana) in WINDOWS_ZONES {
+            assert!(
+                iana.parse::<Tz>().is_ok(),
+                "{windows} maps to unknown {iana}"
+            );
+        }
+    }
+
+    #[test]
+    fn date_only_exdates_remove_that_days_occurrence() {
+        let calendar = Calendar::parse(FIXTURE.as_bytes()).unwrap();
+        assert_eq!(calendar.busy_until(new_york(12, 9, 10)), None);
+        assert_eq!(
+            calendar.busy_until(new_york(19, 9, 10)),
+            Some(new_york(19, 9, 30))
+        );
+        assert_eq!(calendar.next_change(new_york(11, 12, 0)), None);
+        assert_eq!(
+            calendar.next_change(new_york(18, 12, 0)),
+            Some(new_york(19, 9, 0))
+        );
+    }
+
+    #[test]
+    fn events_in_unknown_zones_are_skipped() {
+        let calendar = Calendar::parse(FIXTURE.as_bytes()).unwrap();
+        assert_eq!(calendar.events.len(), 3);
+        assert!(UNKNOWN_ZONES.lock().unwrap().contains("Mars/Olympus_Mons"));
+    }
+
+    /// Parse `events`, written with floating (local) times and plain line
+    /// endings, as a calendar.
+    fn calendar(events: &str) -> Calendar {
+        let ics = format!("BEGIN:VCALENDAR\nVERSION:2.0\n{events}END:VCALENDAR\n");
+        Calendar::parse(ics.replace('\n', "\r\n").as_bytes()).unwrap()
+    }
+
+    fn local(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn monthly_rules_by_weekday_and_by_date() {
+        let calendar = calendar(
+            "BEGIN:VEVENT
+UID:review
+DTSTART:20261030T160000
+DTEND:20261030T170000
+RRULE:FREQ=MONTHLY;BYDAY=-1FR
+END:VEVENT
+BEGIN:VEVENT
+UID:planning
+DTSTART:20261013T100000
+DTEND:20261013T110000
+RRULE:FREQ=MONTHLY;BYDAY=2TU
+END:VEVENT
+BEGIN:VEVENT
+UID:report
+DTSTART:20260131T080000
+DTEND:20260131T083000
+RRULE:FREQ=MONTHLY
+END:VEVENT
+",
+        );
+        // The last Friday of November
+        assert_eq!(calendar.busy_until(local(11, 20, 16, 30)), None);
+        assert_eq!(
+            calendar.busy_until(local(11, 27, 16, 30)),
+            Some(local(11, 27, 17, 0))
+        );
+        // The second Tuesday of November
+        assert_eq!(calendar.busy_until(local(11, 3, 10, 30)), None);
+        assert_eq!(
+            calendar.busy_until(local(11, 10, 10, 30)),
+            Some(local(11, 10, 11, 0))
+        );
+        // The 31st, in months that have one
+        assert_eq!(calendar.busy_until(local(2, 28, 8, 10)), None);
+        assert_eq!(
+            calendar.busy_until(local(3, 31, 8, 10)),
+            Some(local(3, 31, 8, 30))
+        );
+    }
+
+    #[test]
+    fn weekly_rules_with_several_days_and_an_interval() {
+        let calendar = calendar(
+            "BEGIN:VEVENT
+UID:sync
+DTSTART:20261005T100000
+DTEND:20261005T103000
+RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE
+END:VEVENT
+",
+        );
+        let busy = |day| calendar.busy_until(local(10, day, 10, 10)).is_some();
+        // Monday and Wednesday every other week
+        assert!(busy(5) && busy(7));
+        assert!(!busy(6) && !busy(12) && !busy(14));
+        assert!(busy(19) && busy(21));
+    }
+
+    #[test]
+    fn rules_stop_at_their_count_or_until() {
+        let calendar = calendar(
+            "BEGIN:VEVENT
+UID:counted
+DTSTART:20261005T090000
+DTEND:20261005T093000
+RRULE:FREQ=DAILY;COUNT=3
+END:VEVENT
+BEGIN:VEVENT
+UID:until
+DTSTART:20261005T140000
+DTEND:20261005T143000
+RRULE:FREQ=DAILY;UNTIL=20261007T140000
+END:VEVENT
+BEGIN:VEVENT
+UID:until-date
+DTSTART:20261005T170000
+DTEND:20261005T173000
+RRULE:FREQ=DAILY;INTERVAL=2;UNTIL=20261009
+END:VEVENT
+",
+        );
+        let busy = |day, hour| calendar.busy_until(local(10, day, hour, 10)).is_some();
+        assert!(busy(7, 9) && !busy(8, 9));
+        assert!(busy(7, 14) && !busy(8, 14));
+        // A date-only UNTIL includes its own day
+        assert!(busy(7, 17) && !busy(8, 17) && busy(9, 17) && !busy(11, 17));
+    }
+
+    #[test]
+    fn overridden_occurrences_move() {
+        let calendar = calendar(
+            "BEGIN:VEVENT
+UID:standup
+DTSTART:20261005T090000
+DTEND:20261005T091500
+RRULE:FREQ=WEEKLY
+END:VEVENT
+BEGIN:VEVENT
+UID:standup
+RECURRENCE-ID:20261012T090000
+DTSTART:20261012T150000
+DTEND:20261012T153000
+END:VEVENT
+",
+        );
+        assert_eq!(calendar.busy_until(local(10, 12, 9, 10)), None);
+        assert_eq!(
+            calendar.busy_until(local(10, 12, 15, 10)),
+            Some(local(10, 12, 15, 30))
+        );
+        assert_eq!(
+            calendar.busy_until(local(10, 19, 9, 10)),
+            Some(local(10, 19, 9, 15))
+        );
+        assert_eq!(
+            calendar.next_change(local(10, 12, 8, 0)),
+            Some(local(10, 12, 15, 0))
+        );
+    }
+
+    #[test]
+    fn duration_can_stand_in_for_the_end() {
+        let calendar = calendar(
+            "BEGIN:VEVENT
+UID:workshop
+DTSTART:20261005T090000
+DURATION:PT1H30M
+END:VEVENT
+",
+        );
+        assert_eq!(
+            calendar.busy_until(local(10, 5, 10, 0)),
+            Some(local(10, 5, 10, 30))
+        );
+    }
+
+    #[test]
+    fn free_and_cancelled_events_are_not_busy() {
+        let calendar = calendar(
+            "BEGIN:VEVENT
+UID:reminder
+DTSTART:20261005T090000
+DTEND:20261005T100000
+TRANSP:TRANSPARENT
+END:VEVENT
+BEGIN:VEVENT
+UID:called-off
+DTSTART:20261005T090000
+DTEND:20261005T100000
+STATUS:CANCELLED
+END:VEVENT
+",
+        );
+        assert_eq!(calendar, Calendar::default());
+    }
+}
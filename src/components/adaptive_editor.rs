@@ -0,0 +1,111 @@
+use crate::adaptive::{self, AdaptiveSettings, IntervalAdjustment};
+use crate::reminder::ReminderType;
+use dioxus::prelude::*;
+
+/// Editor for adaptive intervals: the bounds, plus why each change since the
+/// last reset was made. Resetting puts the intervals back to what they were
+/// before the first logged change.
+#[component]
+pub fn AdaptiveEditor(
+    settings: Signal<AdaptiveSettings>,
+    log: Signal<Vec<IntervalAdjustment>>,
+    blink_interval: Signal<u64>,
+    posture_interval: Signal<u64>,
+) -> Element {
+    let current = settings();
+    // Newest first, e.g. "Oct 18 14:05 Posture interval 60 s → 75 s."
+    let adjustments: Vec<(String, String)> = log()
+        .into_iter()
+        .rev()
+        .map(|adjustment| {
+            let name = if adjustment.kind == ReminderType::Blink {
+                "Blink"
+            } else {
+                "Posture"
+            };
+            let summary = format!(
+                "{} {name} interval {} s → {} s.",
+                adjustment.at.format("%b %-d %H:%M"),
+                adjustment.from,
+                adjustment.to
+            );
+            (summary, adjustment.reason)
+        })
+        .collect();
+
+    rsx! {
+        div { class: "w-full mb-3",
+            label { class: "flex items-center gap-2 mb-1 text-base font-medium",
+                input {
+                    r#type: "checkbox",
+                    checked: current.enabled,
+                    onchange: move |e| settings.write().enabled = e.checked(),
+                }
+                "Adapt blink and posture intervals"
+            }
+            if current.enabled {
+                div { class: "grid grid-cols-3 gap-1 items-center text-sm mb-1",
+                    span {}
+                    span { class: "text-gray-500", "Min (s)" }
+                    span { class: "text-gray-500", "Max (s)" }
+                    span { "Blink" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        class: "px-1 border border-gray-200 rounded",
+                        value: current.blink_min.to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { settings.write().blink_min = val; },
+                    }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        class: "px-1 border border-gray-200 rounded",
+                        value: current.blink_max.to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { settings.write().blink_max = val; },
+                    }
+                    span { "Posture" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        class: "px-1 border border-gray-200 rounded",
+                        value: current.posture_min.to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { settings.write().posture_min = val; },
+                    }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        class: "px-1 border border-gray-200 rounded",
+                        value: current.posture_max.to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { settings.write().posture_max = val; },
+                    }
+                }
+            }
+            // Listed even with adaptive mode off, so its changes can still be undone
+            if adjustments.is_empty() {
+                if current.enabled {
+                    p { class: "text-sm text-gray-400", "No adjustments yet." }
+                }
+            } else {
+                button {
+                    class: "mb-1 px-2 py-1 rounded bg-gray-200 text-sm hover:bg-gray-300",
+                    onclick: move |_| {
+                        if let Some(val) = adaptive::interval_before(&log.read(), ReminderType::Blink) {
+                            blink_interval.set(val);
+                        }
+                        if let Some(val) = adaptive::interval_before(&log.read(), ReminderType::Posture) {
+                            posture_interval.set(val);
+                        }
+                        log.write().clear();
+                    },
+                    "Reset intervals"
+                }
+            }
+            for (summary, reason) in adjustments {
+                p { class: "text-sm text-gray-600 mb-1",
+                    span { class: "font-medium", "{summary} " }
+                    "{reason}"
+                }
+            }
+        }
+    }
+}
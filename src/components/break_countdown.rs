@@ -0,0 +1,53 @@
+use dioxus::prelude::*;
+use std::time::Duration;
+
+/// Countdown for a timed break, such as the 20-20-20 look-away or a Pomodoro
+/// break. Calls `on_finished` once it reaches zero.
+#[component]
+pub fn BreakCountdown(
+    title: String,
+    instructions: String,
+    seconds: u64,
+    on_finished: EventHandler<()>,
+) -> Element {
+    let mut remaining = use_signal(|| seconds);
+
+    use_future(move || async move {
+        while remaining() > 0 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            remaining.set(remaining() - 1);
+        }
+        on_finished.call(());
+    });
+
+    let progress = if seconds == 0 {
+        100.0
+    } else {
+        100.0 * (seconds - remaining()) as f64 / seconds as f64
+    };
+    // Minute-long breaks read better as mm:ss
+    let (count, unit) = if seconds >= 60 {
+        (
+            format!("{}:{:02}", remaining() / 60, remaining() % 60),
+            "minutes",
+        )
+    } else {
+        (remaining().to_string(), "seconds")
+    };
+
+    rsx! {
+        div {
+            class: "w-[280px] p-4 rounded-xl bg-white/90 shadow-lg flex flex-col items-center text-center",
+            h2 { class: "text-lg font-bold mb-1", "{title}" }
+            p { class: "text-sm text-gray-700 mb-2", "{instructions}" }
+            div { class: "text-5xl font-bold text-blue-700", "{count}" }
+            div { class: "text-xs text-gray-500 mb-2", "{unit}" }
+            div { class: "w-full h-2 rounded bg-gray-200 overflow-hidden",
+                div {
+                    class: "h-2 bg-blue-700",
+                    style: "width: {progress}%; transition: width 1s linear;",
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+/// Editor for meeting awareness: the `.ics` files to read and the optional
+/// break after each meeting.
+#[component]
+pub fn CalendarEditor(
+    files: Signal<Vec<PathBuf>>,
+    post_meeting_break: Signal<bool>,
+    post_meeting_break_duration: Signal<u64>,
+) -> Element {
+    let count = files.read().len();
+
+    rsx! {
+        div { class: "w-full mb-3",
+            label { class: "block mb-1 text-base font-medium", "Hold reminders during meetings from:" }
+            for index in 0..count {
+                div { key: "{index}", class: "flex items-center gap-1 mb-1",
+                    input {
+                        class: "flex-1 px-1 border border-gray-200 rounded text-sm",
+                        placeholder: "/path/to/calendar.ics",
+                        value: files.read()[index].display().to_string(),
+                        oninput: move |e| files.write()[index] = PathBuf::from(e.value()),
+                    }
+                    button {
+                        class: "px-1 text-sm text-gray-500 hover:text-red-600",
+                        title: "Remove",
+                        onclick: move |_| {
+                            files.write().remove(index);
+                        },
+                        "✕"
+                    }
+                }
+            }
+            button {
+                class: "w-full mb-2 py-1 rounded border border-dashed border-gray-300 text-sm text-gray-600 hover:border-blue-500 hover:text-blue-700",
+                onclick: move |_| files.write().push(PathBuf::new()),
+                "+ Add calendar file"
+            }
+            label { class: "flex items-center gap-2 text-sm",
+                input {
+                    r#type: "checkbox",
+                    checked: post_meeting_break(),
+                    onchange: move |e| post_meeting_break.set(e.checked()),
+                }
+                "Take a break after each meeting, for"
+                input {
+                    r#type: "number",
+                    min: "1",
+                    disabled: !post_meeting_break(),
+                    class: "w-16 px-1 border border-gray-200 rounded",
+                    value: post_meeting_break_duration().to_string(),
+                    oninput: move |e| if let Ok(val) = e.value().parse() { post_meeting_break_duration.set(val); },
+                }
+                "s"
+            }
+        }
+    }
+}
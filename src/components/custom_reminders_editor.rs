@@ -0,0 +1,106 @@
+use crate::reminder::CustomReminder;
+use crate::scheduler::MAX_JITTER;
+use dioxus::prelude::*;
+
+/// Editor for user-defined reminders (hydration, stretching, ...).
+/// `next_id` is the id the next added reminder gets.
+#[component]
+pub fn CustomRemindersEditor(
+    reminders: Signal<Vec<CustomReminder>>,
+    next_id: Signal<u32>,
+) -> Element {
+    let count = reminders.read().len();
+
+    rsx! {
+        div { class: "w-full mb-3",
+            label { class: "block mb-1 text-base font-medium", "Custom reminders:" }
+            for index in 0..count {
+                CustomReminderRow { key: "{reminders.read()[index].id}", reminders, index }
+            }
+            button {
+                class: "w-full py-1 rounded border border-dashed border-gray-300 text-sm text-gray-600 hover:border-blue-500 hover:text-blue-700",
+                onclick: move |_| {
+                    let mut reminders = reminders.write();
+                    // Settings saved before the counter existed start it at 0
+                    let id = reminders.iter().map(|reminder| reminder.id + 1).max().unwrap_or(0).max(next_id());
+                    next_id.set(id + 1);
+                    reminders.push(CustomReminder { id, ..Default::default() });
+                },
+                "+ Add reminder"
+            }
+        }
+    }
+}
+
+#[component]
+fn CustomReminderRow(reminders: Signal<Vec<CustomReminder>>, index: usize) -> Element {
+    let reminder = reminders.read()[index].clone();
+
+    rsx! {
+        div { class: "mb-2 p-2 rounded border border-gray-200",
+            div { class: "flex items-center gap-2 mb-1",
+                input {
+                    r#type: "checkbox",
+                    title: "Enabled",
+                    checked: reminder.enabled,
+                    onchange: move |e| reminders.write()[index].enabled = e.checked(),
+                }
+                input {
+                    class: "w-10 px-1 border border-gray-200 rounded text-center",
+                    title: "Icon",
+                    value: reminder.icon,
+                    oninput: move |e| reminders.write()[index].icon = e.value(),
+                }
+                input {
+                    class: "flex-1 px-1 border border-gray-200 rounded text-sm",
+                    placeholder: "Name",
+                    value: reminder.name,
+                    oninput: move |e| reminders.write()[index].name = e.value(),
+                }
+                button {
+                    class: "px-1 text-sm text-gray-500 hover:text-red-600",
+                    title: "Remove",
+                    onclick: move |_| {
+                        reminders.write().remove(index);
+                    },
+                    "✕"
+                }
+            }
+            input {
+                class: "w-full mb-1 px-1 border border-gray-200 rounded text-sm",
+                placeholder: "Message",
+                value: reminder.message,
+                oninput: move |e| reminders.write()[index].message = e.value(),
+            }
+            div { class: "flex items-center gap-1 text-sm",
+                "Every"
+                input {
+                    r#type: "number",
+                    min: "1",
+                    class: "w-20 px-1 border border-gray-200 rounded",
+                    value: reminder.interval.to_string(),
+                    oninput: move |e| if let Ok(val @ 1..) = e.value().parse::<u64>() { reminders.write()[index].interval = val; },
+                }
+                "s, show for"
+                input {
+                    r#type: "number",
+                    min: "1",
+                    class: "w-16 px-1 border border-gray-200 rounded",
+                    value: reminder.duration.to_string(),
+                    oninput: move |e| if let Ok(val) = e.value().parse() { reminders.write()[index].duration = val; },
+                }
+                "s, ±"
+                input {
+                    r#type: "number",
+                    min: "0",
+                    max: MAX_JITTER.to_string(),
+                    title: "Interval jitter",
+                    class: "w-12 px-1 border border-gray-200 rounded",
+                    value: reminder.jitter.to_string(),
+                    oninput: move |e| if let Ok(val) = e.value().parse::<u8>() { reminders.write()[index].jitter = val.min(MAX_JITTER); },
+                }
+                "%"
+            }
+        }
+    }
+}
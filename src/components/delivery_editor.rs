@@ -0,0 +1,298 @@
+use crate::placement::{self, Anchor, MonitorChoice, Rect};
+use crate::reminder::{CustomReminder, DeliverySettings, ReminderType};
+use crate::shared_state::BlinkSettings;
+use crate::sink::SinkKind;
+use crate::sound::{self, SoundCue};
+use dioxus::desktop::window;
+use dioxus::prelude::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Width of the placement preview, in CSS pixels.
+const PREVIEW_WIDTH: f64 = 280.0;
+
+/// Editor for how each reminder type is delivered: window, notification or
+/// log, sound, Do Not Disturb, monitor, position, size and opacity, with a
+/// preview of where the popup will appear.
+#[component]
+pub fn DeliveryEditor(
+    delivery: Signal<BTreeMap<String, DeliverySettings>>,
+    custom_reminders: Signal<Vec<CustomReminder>>,
+    sound_muted: Signal<bool>,
+) -> Element {
+    // Names follow the custom reminders being edited, not the saved ones
+    let names = BlinkSettings {
+        custom_reminders: custom_reminders(),
+        ..Default::default()
+    };
+    let kinds = names.all_reminder_kinds();
+    let mut selected = use_signal(|| 0usize);
+    let kind = kinds
+        .get(selected())
+        .copied()
+        .unwrap_or(ReminderType::Blink);
+    let options = delivery
+        .read()
+        .get(&kind.key())
+        .cloned()
+        .unwrap_or_default();
+    let custom_sound = matches!(options.sound, SoundCue::File(_));
+    let file_index = SoundCue::BUILT_IN.len();
+    let sound_path = match &options.sound {
+        SoundCue::File(path) => path.display().to_string(),
+        _ => String::new(),
+    };
+
+    rsx! {
+        div { class: "w-full mb-3",
+            label { class: "block mb-1 text-base font-medium", "Delivery" }
+            label { class: "flex items-center gap-2 mb-1 text-sm",
+                input {
+                    r#type: "checkbox",
+                    checked: sound_muted(),
+                    onchange: move |e| sound_muted.set(e.checked()),
+                }
+                "Mute all sounds"
+            }
+            select {
+                class: "w-full mb-2 px-1 border border-gray-200 rounded text-sm",
+                onchange: move |e| selected.set(e.value().parse().unwrap_or(0)),
+                for (index, other) in kinds.iter().enumerate() {
+                    option {
+                        value: "{index}",
+                        selected: index == selected(),
+                        {names.reminder_name(*other)}
+                    }
+                }
+            }
+            div { class: "grid grid-cols-2 gap-1 items-center text-sm",
+                span { "Show as" }
+                select {
+                    class: "px-1 border border-gray-200 rounded",
+                    onchange: move |e| {
+                        let sink = e.value().parse::<usize>().ok().and_then(|index| SinkKind::ALL.get(index));
+                        if let Some(&sink) = sink {
+                            delivery.write().entry(kind.key()).or_default().sink = sink;
+                        }
+                    },
+                    for (index, sink) in SinkKind::ALL.into_iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: sink == options.sink,
+                            {sink.label()}
+                        }
+                    }
+                }
+                span { "Sound" }
+                select {
+                    class: "px-1 border border-gray-200 rounded",
+                    onchange: move |e| {
+                        // The last option switches to a file, keeping the path if there is one
+                        let index = e.value().parse::<usize>().unwrap_or(0);
+                        let mut delivery = delivery.write();
+                        let options = delivery.entry(kind.key()).or_default();
+                        options.sound = match SoundCue::BUILT_IN.get(index) {
+                            Some(cue) => cue.clone(),
+                            None if matches!(options.sound, SoundCue::File(_)) => options.sound.clone(),
+                            None => SoundCue::File(PathBuf::new()),
+                        };
+                    },
+                    for (index, cue) in SoundCue::BUILT_IN.into_iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: cue == options.sound,
+                            {cue.label()}
+                        }
+                    }
+                    option {
+                        value: "{file_index}",
+                        selected: custom_sound,
+                        {SoundCue::File(PathBuf::new()).label()}
+                    }
+                }
+                if custom_sound {
+                    span { class: "text-gray-500", "WAV or OGG file" }
+                    input {
+                        class: "px-1 border border-gray-200 rounded",
+                        placeholder: "/path/to/sound.ogg",
+                        value: sound_path,
+                        oninput: move |e| delivery.write().entry(kind.key()).or_default().sound = SoundCue::File(PathBuf::from(e.value())),
+                    }
+                }
+                span { "Volume (%)" }
+                div { class: "flex items-center gap-1",
+                    input {
+                        r#type: "range",
+                        min: "0",
+                        max: "100",
+                        class: "flex-1 min-w-0",
+                        value: (options.volume * 100.0).round().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse::<f64>() {
+                            delivery.write().entry(kind.key()).or_default().volume = (val / 100.0).clamp(0.0, 1.0);
+                        },
+                    }
+                    button {
+                        class: "px-1 text-blue-700 hover:underline",
+                        title: "Play the sound",
+                        onclick: {
+                            let options = options.clone();
+                            move |_| sound::play(&options.sound, options.volume as f32)
+                        },
+                        "Test"
+                    }
+                }
+                span { "Respect Do Not Disturb" }
+                input {
+                    r#type: "checkbox",
+                    checked: options.respect_dnd,
+                    onchange: move |e| delivery.write().entry(kind.key()).or_default().respect_dnd = e.checked(),
+                }
+                span { "Monitor" }
+                select {
+                    class: "px-1 border border-gray-200 rounded",
+                    onchange: move |e| {
+                        let choice = e.value().parse::<usize>().ok().and_then(|index| MonitorChoice::ALL.get(index));
+                        if let Some(&choice) = choice {
+                            delivery.write().entry(kind.key()).or_default().monitor = choice;
+                        }
+                    },
+                    for (index, choice) in MonitorChoice::ALL.into_iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: choice == options.monitor,
+                            {choice.label()}
+                        }
+                    }
+                }
+                span { "Position" }
+                select {
+                    class: "px-1 border border-gray-200 rounded",
+                    onchange: move |e| {
+                        let anchor = e.value().parse::<usize>().ok().and_then(|index| Anchor::ALL.get(index));
+                        if let Some(&anchor) = anchor {
+                            // Picking an anchor replaces a dragged position
+                            let mut delivery = delivery.write();
+                            let options = delivery.entry(kind.key()).or_default();
+                            options.anchor = anchor;
+                            options.dragged_position = None;
+                        }
+                    },
+                    for (index, anchor) in Anchor::ALL.into_iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: anchor == options.anchor,
+                            {anchor.label()}
+                        }
+                    }
+                }
+                span { "Margin (px)" }
+                input {
+                    r#type: "number",
+                    min: "0",
+                    class: "px-1 border border-gray-200 rounded",
+                    value: options.margin.to_string(),
+                    oninput: move |e| if let Ok(val) = e.value().parse() {
+                        delivery.write().entry(kind.key()).or_default().margin = val;
+                    },
+                }
+                span { "Size (%)" }
+                input {
+                    r#type: "number",
+                    min: "25",
+                    max: "400",
+                    class: "px-1 border border-gray-200 rounded",
+                    value: (options.scale * 100.0).round().to_string(),
+                    oninput: move |e| if let Ok(val) = e.value().parse::<f64>() {
+                        delivery.write().entry(kind.key()).or_default().scale = (val / 100.0).clamp(0.25, 4.0);
+                    },
+                }
+                span { "Opacity (%)" }
+                input {
+                    r#type: "range",
+                    min: "20",
+                    max: "100",
+                    value: (options.opacity * 100.0).round().to_string(),
+                    oninput: move |e| if let Ok(val) = e.value().parse::<f64>() {
+                        delivery.write().entry(kind.key()).or_default().opacity = (val / 100.0).clamp(0.2, 1.0);
+                    },
+                }
+            }
+            if options.dragged_position.is_some() {
+                div { class: "flex items-center justify-between mt-1 text-sm text-gray-600",
+                    span { "Placed where you last dragged it." }
+                    button {
+                        class: "px-1 text-blue-700 hover:underline",
+                        onclick: move |_| delivery.write().entry(kind.key()).or_default().dragged_position = None,
+                        "Reset"
+                    }
+                }
+            }
+            PlacementPreview { kind, options: options.clone() }
+        }
+    }
+}
+
+/// A miniature of the primary monitor showing where a `kind` popup will
+/// appear with `options`. A cursor in the middle of the screen stands in for
+/// the real one.
+#[component]
+fn PlacementPreview(kind: ReminderType, options: DeliverySettings) -> Element {
+    let desktop = window();
+    let (area, scale_factor) = desktop
+        .primary_monitor()
+        .or_else(|| desktop.available_monitors().next())
+        .map(|monitor| {
+            let area = Rect {
+                x: monitor.position().x.into(),
+                y: monitor.position().y.into(),
+                width: monitor.size().width.into(),
+                height: monitor.size().height.into(),
+            };
+            (area, monitor.scale_factor())
+        })
+        .unwrap_or((
+            Rect {
+                x: 0.0,
+                y: 0.0,
+                width: 1920.0,
+                height: 1080.0,
+            },
+            1.0,
+        ));
+    let (width, height) = placement::base_size(kind);
+    let (width, height) = (
+        width * options.scale * scale_factor,
+        height * options.scale * scale_factor,
+    );
+    let cursor = (area.x + area.width / 2.0, area.y + area.height / 2.0);
+    let (x, y) =
+        placement::window_position(area, width, height, scale_factor, &options, Some(cursor));
+
+    // Screen pixels to preview pixels
+    let ratio = PREVIEW_WIDTH / area.width;
+    let screen_height = area.height * ratio;
+    let left = (x - area.x) * ratio;
+    let top = (y - area.y) * ratio;
+    let (width, height) = (width * ratio, height * ratio);
+    let opacity = options.opacity;
+    let show_cursor = options.anchor == Anchor::NearCursor && options.dragged_position.is_none();
+    let cursor_left = PREVIEW_WIDTH / 2.0 - 2.0;
+    let cursor_top = screen_height / 2.0 - 2.0;
+
+    rsx! {
+        div {
+            class: "mt-2 rounded border border-gray-300 bg-gray-100",
+            style: "position: relative; width: {PREVIEW_WIDTH}px; height: {screen_height}px; overflow: hidden;",
+            div {
+                class: "rounded bg-blue-500 shadow",
+                style: "position: absolute; left: {left}px; top: {top}px; width: {width}px; height: {height}px; opacity: {opacity};",
+            }
+            if show_cursor {
+                div {
+                    class: "rounded-full bg-gray-700",
+                    style: "position: absolute; left: {cursor_left}px; top: {cursor_top}px; width: 4px; height: 4px;",
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,13 @@
+pub mod adaptive_editor;
 pub mod animated_blink;
 pub mod animated_posture;
+pub mod break_countdown;
+pub mod calendar_editor;
+pub mod custom_reminders_editor;
+pub mod delivery_editor;
+pub mod pomodoro_editor;
+pub mod reminder_content;
 pub mod reminder_window;
+pub mod schedule_editor;
 pub mod settings_window;
+pub mod statistics_window;
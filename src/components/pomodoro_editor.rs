@@ -0,0 +1,57 @@
+use crate::pomodoro::PomodoroSettings;
+use dioxus::prelude::*;
+
+/// Editor for Pomodoro mode: work and break lengths and the cycle count.
+#[component]
+pub fn PomodoroEditor(settings: Signal<PomodoroSettings>) -> Element {
+    let current = settings();
+
+    rsx! {
+        div { class: "w-full mb-3",
+            label { class: "flex items-center gap-2 mb-1 text-base font-medium",
+                input {
+                    r#type: "checkbox",
+                    checked: current.enabled,
+                    onchange: move |e| settings.write().enabled = e.checked(),
+                }
+                "Pomodoro mode"
+            }
+            if current.enabled {
+                div { class: "grid grid-cols-2 gap-1 items-center text-sm",
+                    span { "Work (minutes)" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        class: "px-1 border border-gray-200 rounded",
+                        value: current.work_minutes.to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { settings.write().work_minutes = val; },
+                    }
+                    span { "Short break (minutes)" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        class: "px-1 border border-gray-200 rounded",
+                        value: current.short_break_minutes.to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { settings.write().short_break_minutes = val; },
+                    }
+                    span { "Long break (minutes)" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        class: "px-1 border border-gray-200 rounded",
+                        value: current.long_break_minutes.to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { settings.write().long_break_minutes = val; },
+                    }
+                    span { "Sessions before a long break" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        class: "px-1 border border-gray-200 rounded",
+                        value: current.cycles_before_long_break.to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { settings.write().cycles_before_long_break = val; },
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use dioxus::prelude::*;
+
+/// Generic reminder body: a large icon, a title and a message.
+#[component]
+pub fn ReminderContent(icon: String, title: String, message: String) -> Element {
+    rsx! {
+        div {
+            class: "w-[260px] p-4 rounded-xl bg-white/90 shadow-lg flex flex-col items-center text-center",
+            div { class: "text-5xl mb-2", "{icon}" }
+            h2 { class: "text-lg font-bold mb-1", "{title}" }
+            p { class: "text-sm text-gray-700", "{message}" }
+        }
+    }
+}
@@ -1,39 +1,224 @@
 use crate::components::animated_blink::AnimatedBlink;
 use crate::components::animated_posture::AnimatedPosture;
-use crate::reminder::ReminderType;
-use crate::shared_state::{get_blink_duration, get_posture_duration};
+use crate::components::break_countdown::BreakCountdown;
+use crate::components::reminder_content::ReminderContent;
+use crate::reminder::{EscalationLevel, ReminderOutcome, ReminderType};
+use crate::scheduler::SchedulerCommand;
+use crate::shared_state::{
+    answer_showing, answered_receiver, get_blink_duration, get_custom_reminder, get_delivery_for,
+    get_eye_break_duration, get_pomodoro, get_post_meeting_break_duration, get_posture_duration,
+    remember_position, send_command,
+};
+use crate::STYLE;
 use dioxus::desktop::use_window;
 use dioxus::prelude::*;
 use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
 
 #[derive(Props, Clone, PartialEq)]
 pub struct ReminderWindowProps {
     pub kind: ReminderType,
     pub duration: Option<u64>,
+    pub level: EscalationLevel,
+    /// Shared by the windows showing this reminder on different monitors.
+    pub showing: u64,
 }
 
 pub fn reminder_window(props: ReminderWindowProps) -> Element {
     let win = use_window();
+    let kind = props.kind;
+    let showing = props.showing;
+    let options = get_delivery_for(kind);
+    let mut dragged = use_signal(|| false);
+
+    // Tell the scheduler how the user answered, then close the window. Only
+    // the first answer among the windows of one showing counts.
+    let respond = use_callback({
+        let win = win.clone();
+        move |outcome: ReminderOutcome| {
+            // Show the next one where the user moved this one
+            if dragged() {
+                if let (Ok(position), Some(monitor)) = (win.outer_position(), win.current_monitor())
+                {
+                    let scale_factor = monitor.scale_factor();
+                    remember_position(
+                        kind,
+                        (
+                            f64::from(position.x - monitor.position().x) / scale_factor,
+                            f64::from(position.y - monitor.position().y) / scale_factor,
+                        ),
+                    );
+                }
+            }
+            if answer_showing(showing) {
+                send_command(SchedulerCommand::Respond { kind, outcome });
+            }
+            win.close();
+        }
+    });
+
+    // Close along with the windows on other monitors
+    use_future({
+        let win = win.clone();
+        move || {
+            let win = win.clone();
+            async move {
+                let mut answered = answered_receiver();
+                loop {
+                    match answered.recv().await {
+                        Ok(id) if id == showing => break,
+                        Err(RecvError::Closed) => return,
+                        _ => {}
+                    }
+                }
+                win.close();
+            }
+        }
+    });
 
     // Determine duration and animation based on reminder type
-    let (default_duration, content) = match props.kind {
+    let (default_duration, content) = match kind {
         ReminderType::Blink => (get_blink_duration(), rsx! { AnimatedBlink {} }),
         ReminderType::Posture => (get_posture_duration(), rsx! { AnimatedPosture {} }),
+        ReminderType::EyeBreak => {
+            let seconds = props.duration.unwrap_or_else(get_eye_break_duration);
+            (
+                seconds,
+                // A finished countdown means the break was taken
+                rsx! {
+                    BreakCountdown {
+                        title: "Eye break",
+                        instructions: "Look at something at least 20 feet (6 m) away until the timer runs out.",
+                        seconds,
+                        on_finished: move |_| respond(ReminderOutcome::Done),
+                    }
+                },
+            )
+        }
+        ReminderType::MeetingBreak => {
+            let seconds = props
+                .duration
+                .unwrap_or_else(get_post_meeting_break_duration);
+            (
+                seconds,
+                rsx! {
+                    BreakCountdown {
+                        title: "Meeting's over",
+                        instructions: "Stand up, stretch and rest your eyes before getting back to work.",
+                        seconds,
+                        on_finished: move |_| respond(ReminderOutcome::Done),
+                    }
+                },
+            )
+        }
+        ReminderType::PomodoroBreak { long } => {
+            let pomodoro = get_pomodoro();
+            let minutes = if long {
+                pomodoro.long_break_minutes
+            } else {
+                pomodoro.short_break_minutes
+            };
+            let seconds = props.duration.unwrap_or(minutes * 60);
+            let title = if long { "Long break" } else { "Short break" };
+            (
+                seconds,
+                rsx! {
+                    BreakCountdown {
+                        title: title.to_string(),
+                        instructions: "Step away from the screen. The next work session starts when the timer runs out.",
+                        seconds,
+                        on_finished: move |_| respond(ReminderOutcome::Done),
+                    }
+                },
+            )
+        }
+        ReminderType::Custom(id) => {
+            let reminder = get_custom_reminder(id).unwrap_or_default();
+            (
+                reminder.duration,
+                rsx! { ReminderContent { icon: reminder.icon, title: reminder.name, message: reminder.message } },
+            )
+        }
+    };
+    // Escalated reminders stay up longer
+    let duration = props.duration.unwrap_or(default_duration) * props.level.duration_factor();
+    // The window itself is already scaled; this scales the content to match
+    let scale = props.level.scale() * options.scale;
+    let opacity = options.opacity;
+    let backdrop = if props.level == EscalationLevel::Fullscreen {
+        "rgba(0,0,0,0.6)"
+    } else {
+        "rgba(255,255,255,0.0)"
     };
-    let duration = props.duration.unwrap_or(default_duration);
 
+    // Break countdowns close their own window when they finish; other
+    // reminders close after `duration` and count as ignored
     use_effect(move || {
-        let win = win.clone();
+        if matches!(
+            kind,
+            ReminderType::EyeBreak
+                | ReminderType::MeetingBreak
+                | ReminderType::PomodoroBreak { .. }
+        ) {
+            return;
+        }
         spawn(async move {
             tokio::time::sleep(Duration::from_secs(duration)).await;
-            win.close();
+            respond(ReminderOutcome::Ignored);
         });
     });
 
+    // Grab keyboard focus so Esc and S work without clicking first
+    use_effect({
+        let win = win.clone();
+        move || win.set_focus()
+    });
+
     rsx! {
+        document::Link { rel: "stylesheet", href: STYLE }
         div {
-            style: "width: 100vw; height: 100vh; overflow: hidden; display: flex; align-items: center; justify-content: center; background: rgba(255,255,255,0.0);",
-            {content}
+            style: "width: 100vw; height: 100vh; overflow: hidden; display: flex; flex-direction: column; align-items: center; justify-content: center; background: {backdrop}; outline: none;",
+            tabindex: "0",
+            autofocus: true,
+            onkeydown: move |e| match e.key() {
+                Key::Escape => respond(ReminderOutcome::Skipped),
+                Key::Enter => respond(ReminderOutcome::Done),
+                Key::Character(c) if c.eq_ignore_ascii_case("s") => respond(ReminderOutcome::Snoozed),
+                _ => {}
+            },
+            div {
+                style: "display: flex; flex-direction: column; align-items: center; transform: scale({scale}); opacity: {opacity};",
+                div {
+                    class: "px-2 text-gray-400 cursor-move select-none",
+                    title: "Drag to move. The position is remembered.",
+                    onmousedown: move |_| {
+                        dragged.set(true);
+                        win.drag();
+                    },
+                    "⠿"
+                }
+                {content}
+                div { class: "flex gap-2 mt-2",
+                    button {
+                        class: "px-2 py-1 rounded bg-blue-700 text-white text-sm shadow hover:bg-blue-800",
+                        title: "Enter",
+                        onclick: move |_| respond(ReminderOutcome::Done),
+                        "Done"
+                    }
+                    button {
+                        class: "px-2 py-1 rounded bg-white/80 text-gray-800 text-sm shadow hover:bg-white",
+                        title: "S",
+                        onclick: move |_| respond(ReminderOutcome::Snoozed),
+                        "Snooze 5 min"
+                    }
+                    button {
+                        class: "px-2 py-1 rounded bg-white/80 text-gray-800 text-sm shadow hover:bg-white",
+                        title: "Esc",
+                        onclick: move |_| respond(ReminderOutcome::Skipped),
+                        "Skip"
+                    }
+                }
+            }
         }
     }
 }
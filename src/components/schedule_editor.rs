@@ -0,0 +1,90 @@
+use crate::schedule::{TimeRange, WeeklySchedule};
+use chrono::{NaiveTime, Weekday};
+use dioxus::prelude::*;
+
+/// Editor for the working-hours schedule: one row of time ranges per weekday.
+#[component]
+pub fn ScheduleEditor(schedule: Signal<WeeklySchedule>) -> Element {
+    let enabled = schedule.read().enabled;
+
+    rsx! {
+        div { class: "w-full mb-3",
+            label { class: "flex items-center gap-2 mb-1 text-base font-medium",
+                input {
+                    r#type: "checkbox",
+                    checked: enabled,
+                    onchange: move |e| schedule.write().enabled = e.checked(),
+                }
+                "Only remind me during working hours"
+            }
+            if enabled {
+                for day in WeeklySchedule::WEEKDAYS {
+                    DayRow { key: "{day}", schedule, day }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DayRow(schedule: Signal<WeeklySchedule>, day: Weekday) -> Element {
+    let ranges = schedule.read().day(day).to_vec();
+
+    rsx! {
+        div { class: "flex items-start gap-2 mb-1",
+            span { class: "w-10 pt-1 text-sm font-medium", "{day}" }
+            div { class: "flex-1 flex flex-col gap-1",
+                if ranges.is_empty() {
+                    span { class: "pt-1 text-sm text-gray-400", "Off" }
+                }
+                for (index, range) in ranges.into_iter().enumerate() {
+                    div { key: "{index}", class: "flex items-center gap-1",
+                        input {
+                            r#type: "time",
+                            class: "px-1 border border-gray-200 rounded text-sm",
+                            value: range.start.format("%H:%M").to_string(),
+                            oninput: move |e| {
+                                if let Ok(time) = NaiveTime::parse_from_str(&e.value(), "%H:%M") {
+                                    schedule.write().day_mut(day)[index].start = time;
+                                }
+                            },
+                        }
+                        span { class: "text-sm", "–" }
+                        input {
+                            r#type: "time",
+                            class: "px-1 border border-gray-200 rounded text-sm",
+                            value: range.end.format("%H:%M").to_string(),
+                            oninput: move |e| {
+                                if let Ok(time) = NaiveTime::parse_from_str(&e.value(), "%H:%M") {
+                                    schedule.write().day_mut(day)[index].end = time;
+                                }
+                            },
+                        }
+                        button {
+                            class: "px-1 text-sm text-gray-500 hover:text-red-600",
+                            title: "Remove",
+                            onclick: move |_| {
+                                schedule.write().day_mut(day).remove(index);
+                            },
+                            "✕"
+                        }
+                    }
+                }
+            }
+            button {
+                class: "px-1 text-sm text-blue-700 hover:text-blue-900",
+                title: "Add a time range",
+                onclick: move |_| {
+                    schedule
+                        .write()
+                        .day_mut(day)
+                        .push(TimeRange::new(
+                            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                            NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+                        ));
+                },
+                "+"
+            }
+        }
+    }
+}
@@ -1,7 +1,20 @@
 use crate::{
+    components::{
+        adaptive_editor::AdaptiveEditor, calendar_editor::CalendarEditor,
+        custom_reminders_editor::CustomRemindersEditor, delivery_editor::DeliveryEditor,
+        pomodoro_editor::PomodoroEditor, schedule_editor::ScheduleEditor,
+    },
+    scheduler::MAX_JITTER,
     shared_state::{
-        get_blink_duration, get_blink_interval, get_posture_duration, get_posture_interval,
-        set_blink_duration, set_blink_interval, set_posture_duration, set_posture_interval,
+        config_warning, get_adaptive, get_adaptive_log, get_blink_duration, get_blink_interval,
+        get_blink_jitter, get_calendar_files, get_custom_reminders, get_defer_during_fullscreen,
+        get_delivery, get_escalate_after, get_eye_break_duration, get_eye_break_enabled,
+        get_eye_break_interval, get_eye_break_jitter, get_fullscreen_after,
+        get_history_retention_days, get_idle_break_resets_blink, get_idle_break_threshold,
+        get_idle_threshold, get_max_fullscreen_deferral, get_next_custom_id, get_pomodoro,
+        get_post_meeting_break, get_post_meeting_break_duration, get_posture_duration,
+        get_posture_interval, get_posture_jitter, get_schedule, get_settings, get_sound_muted,
+        save_edits,
     },
     STYLE,
 };
@@ -11,16 +24,49 @@ use dioxus::prelude::*;
 pub fn SettingsWindow() -> Element {
     let mut local_interval = use_signal(get_blink_interval);
     let mut local_duration = use_signal(get_blink_duration);
+    let mut local_blink_jitter = use_signal(get_blink_jitter);
     let mut local_posture_interval = use_signal(get_posture_interval);
     let mut local_posture_duration = use_signal(get_posture_duration);
+    let mut local_posture_jitter = use_signal(get_posture_jitter);
+    let mut local_eye_break_enabled = use_signal(get_eye_break_enabled);
+    let mut local_eye_break_interval = use_signal(get_eye_break_interval);
+    let mut local_eye_break_duration = use_signal(get_eye_break_duration);
+    let mut local_eye_break_jitter = use_signal(get_eye_break_jitter);
+    let mut local_idle_threshold = use_signal(get_idle_threshold);
+    let mut local_idle_break_threshold = use_signal(get_idle_break_threshold);
+    let mut local_idle_break_resets_blink = use_signal(get_idle_break_resets_blink);
+    let mut local_defer_during_fullscreen = use_signal(get_defer_during_fullscreen);
+    let mut local_max_fullscreen_deferral = use_signal(get_max_fullscreen_deferral);
+    let mut local_escalate_after = use_signal(get_escalate_after);
+    let mut local_fullscreen_after = use_signal(get_fullscreen_after);
+    let mut local_history_retention_days = use_signal(get_history_retention_days);
+    let local_calendar_files = use_signal(get_calendar_files);
+    let local_post_meeting_break = use_signal(get_post_meeting_break);
+    let local_post_meeting_break_duration = use_signal(get_post_meeting_break_duration);
+    let local_schedule = use_signal(get_schedule);
+    let local_custom_reminders = use_signal(get_custom_reminders);
+    let local_next_custom_id = use_signal(get_next_custom_id);
+    let local_pomodoro = use_signal(get_pomodoro);
+    let local_adaptive = use_signal(get_adaptive);
+    let local_adaptive_log = use_signal(get_adaptive_log);
+    let local_delivery = use_signal(get_delivery);
+    let local_sound_muted = use_signal(get_sound_muted);
+    let mut warning = use_signal(config_warning);
+    // What the form started from, so Save only applies what was edited here
+    let mut opened = use_signal(get_settings);
 
     rsx! {
         document::Link { rel: "stylesheet", href: STYLE }
         div {
-            class: "w-screen h-screen flex items-center justify-center bg-gradient-to-br from-white to-blue-50",
+            class: "w-screen min-h-screen py-6 flex items-center justify-center bg-gradient-to-br from-white to-blue-50",
             div {
                 class: "bg-white p-5 rounded-xl w-[320px] shadow-lg flex flex-col items-center",
                 h2 { class: "text-2xl font-bold mb-4 text-center", "Blinkion Settings" }
+                if let Some(message) = warning() {
+                    div { class: "w-full mb-3 px-2 py-1 rounded bg-yellow-100 text-yellow-800 text-sm",
+                        "{message}"
+                    }
+                }
                 div { class: "w-full mb-3",
                     label { class: "block mb-1 text-base font-medium", "Blink interval (seconds):" }
                     input {
@@ -28,7 +74,7 @@ pub fn SettingsWindow() -> Element {
                         min: "1",
                         class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-blue-500 text-base",
                         value: local_interval().to_string(),
-                        oninput: move |e| if let Ok(val) = e.value().parse() { local_interval.set(val); },
+                        oninput: move |e| if let Ok(val @ 1..) = e.value().parse::<u64>() { local_interval.set(val); },
                     }
                 }
                 div { class: "w-full mb-3",
@@ -40,6 +86,15 @@ pub fn SettingsWindow() -> Element {
                         value: local_duration().to_string(),
                         oninput: move |e| if let Ok(val) = e.value().parse() { local_duration.set(val); },
                     }
+                    label { class: "block mb-1 text-base font-medium", "Blink interval jitter (±%):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        max: MAX_JITTER.to_string(),
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-blue-500 text-base",
+                        value: local_blink_jitter().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse::<u8>() { local_blink_jitter.set(val.min(MAX_JITTER)); },
+                    }
                 }
                 div { class: "w-full mb-3",
                     label { class: "block mb-1 text-base font-medium", "Posture interval (seconds):" }
@@ -48,33 +103,201 @@ pub fn SettingsWindow() -> Element {
                         min: "1",
                         class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-green-500 text-base",
                         value: local_posture_interval().to_string(),
-                        oninput: move |e| if let Ok(val) = e.value().parse() { local_posture_interval.set(val); },
+                        oninput: move |e| if let Ok(val @ 1..) = e.value().parse::<u64>() { local_posture_interval.set(val); },
                     }
                 }
-                div { class: "w-full mb-4",
+                div { class: "w-full mb-3",
                     label { class: "block mb-1 text-base font-medium", "Posture duration (seconds):" }
                     input {
                         r#type: "number",
                         min: "1",
-                        class: "w-full px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-green-500 text-base",
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-green-500 text-base",
                         value: local_posture_duration().to_string(),
                         oninput: move |e| if let Ok(val) = e.value().parse() { local_posture_duration.set(val); },
                     }
+                    label { class: "block mb-1 text-base font-medium", "Posture interval jitter (±%):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        max: MAX_JITTER.to_string(),
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-green-500 text-base",
+                        value: local_posture_jitter().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse::<u8>() { local_posture_jitter.set(val.min(MAX_JITTER)); },
+                    }
+                }
+                div { class: "w-full mb-3",
+                    label { class: "flex items-center gap-2 mb-1 text-base font-medium",
+                        input {
+                            r#type: "checkbox",
+                            checked: local_eye_break_enabled(),
+                            onchange: move |e| local_eye_break_enabled.set(e.checked()),
+                        }
+                        "20-20-20 eye breaks"
+                    }
+                    label { class: "block mb-1 text-base font-medium", "Eye break interval (seconds):" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        disabled: !local_eye_break_enabled(),
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-blue-500 text-base",
+                        value: local_eye_break_interval().to_string(),
+                        oninput: move |e| if let Ok(val @ 1..) = e.value().parse::<u64>() { local_eye_break_interval.set(val); },
+                    }
+                    label { class: "block mb-1 text-base font-medium", "Eye break countdown (seconds):" }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        disabled: !local_eye_break_enabled(),
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-blue-500 text-base",
+                        value: local_eye_break_duration().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { local_eye_break_duration.set(val); },
+                    }
+                    label { class: "block mb-1 text-base font-medium", "Eye break interval jitter (±%):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        max: MAX_JITTER.to_string(),
+                        disabled: !local_eye_break_enabled(),
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-blue-500 text-base",
+                        value: local_eye_break_jitter().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse::<u8>() { local_eye_break_jitter.set(val.min(MAX_JITTER)); },
+                    }
+                }
+                div { class: "w-full mb-3",
+                    label { class: "block mb-1 text-base font-medium", "Pause when idle for (seconds, 0 = never):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-gray-500 text-base",
+                        value: local_idle_threshold().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { local_idle_threshold.set(val); },
+                    }
+                }
+                div { class: "w-full mb-4",
+                    label { class: "block mb-1 text-base font-medium", "Idle time that counts as a break (seconds, 0 = never):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-gray-500 text-base",
+                        value: local_idle_break_threshold().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { local_idle_break_threshold.set(val); },
+                    }
+                    label { class: "flex items-center gap-2 text-base",
+                        input {
+                            r#type: "checkbox",
+                            checked: local_idle_break_resets_blink(),
+                            onchange: move |e| local_idle_break_resets_blink.set(e.checked()),
+                        }
+                        "Also restart the blink countdown"
+                    }
+                }
+                div { class: "w-full mb-3",
+                    label { class: "flex items-center gap-2 mb-1 text-base font-medium",
+                        input {
+                            r#type: "checkbox",
+                            checked: local_defer_during_fullscreen(),
+                            onchange: move |e| local_defer_during_fullscreen.set(e.checked()),
+                        }
+                        "Hold reminders while a fullscreen app is active"
+                    }
+                    label { class: "block mb-1 text-base font-medium", "Hold for at most (seconds, 0 = until it ends):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        disabled: !local_defer_during_fullscreen(),
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-gray-500 text-base",
+                        value: local_max_fullscreen_deferral().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { local_max_fullscreen_deferral.set(val); },
+                    }
+                }
+                div { class: "w-full mb-3",
+                    label { class: "block mb-1 text-base font-medium", "Bigger popup after ignoring (reminders in a row, 0 = never):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-red-500 text-base",
+                        value: local_escalate_after().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { local_escalate_after.set(val); },
+                    }
+                    label { class: "block mb-1 text-base font-medium", "Full-screen after ignoring (reminders in a row, 0 = never):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-red-500 text-base",
+                        value: local_fullscreen_after().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { local_fullscreen_after.set(val); },
+                    }
+                }
+                div { class: "w-full mb-3",
+                    label { class: "block mb-1 text-base font-medium", "Keep reminder history for (days, 0 = forever):" }
+                    input {
+                        r#type: "number",
+                        min: "0",
+                        class: "w-full mb-2 px-2 py-1 border border-gray-200 rounded focus:outline-none focus:border-gray-500 text-base",
+                        value: local_history_retention_days().to_string(),
+                        oninput: move |e| if let Ok(val) = e.value().parse() { local_history_retention_days.set(val); },
+                    }
+                }
+                CustomRemindersEditor { reminders: local_custom_reminders, next_id: local_next_custom_id }
+                ScheduleEditor { schedule: local_schedule }
+                CalendarEditor {
+                    files: local_calendar_files,
+                    post_meeting_break: local_post_meeting_break,
+                    post_meeting_break_duration: local_post_meeting_break_duration,
+                }
+                PomodoroEditor { settings: local_pomodoro }
+                AdaptiveEditor {
+                    settings: local_adaptive,
+                    log: local_adaptive_log,
+                    blink_interval: local_interval,
+                    posture_interval: local_posture_interval,
+                }
+                DeliveryEditor {
+                    delivery: local_delivery,
+                    custom_reminders: local_custom_reminders,
+                    sound_muted: local_sound_muted,
                 }
                 button {
                     class: "w-full py-2 rounded bg-blue-700 text-white text-base font-semibold shadow hover:bg-blue-800 transition focus:outline-none focus:ring-2 focus:ring-blue-400",
                     onclick: move |_| {
-                        set_blink_interval(local_interval());
-                        set_blink_duration(local_duration());
-                        set_posture_interval(local_posture_interval());
-                        set_posture_duration(local_posture_duration());
-                        println!(
-                            "Saved: interval={} duration={} posture_interval={} posture_duration={}",
-                            local_interval(),
-                            local_duration(),
-                            local_posture_interval(),
-                            local_posture_duration()
-                        );
+                        let mut settings = opened();
+                        settings.blink_interval = local_interval();
+                        settings.blink_duration = local_duration();
+                        settings.blink_jitter = local_blink_jitter();
+                        settings.posture_interval = local_posture_interval();
+                        settings.posture_duration = local_posture_duration();
+                        settings.posture_jitter = local_posture_jitter();
+                        settings.eye_break_enabled = local_eye_break_enabled();
+                        settings.eye_break_interval = local_eye_break_interval();
+                        settings.eye_break_duration = local_eye_break_duration();
+                        settings.eye_break_jitter = local_eye_break_jitter();
+                        settings.idle_threshold = local_idle_threshold();
+                        settings.idle_break_threshold = local_idle_break_threshold();
+                        settings.idle_break_resets_blink = local_idle_break_resets_blink();
+                        settings.defer_during_fullscreen = local_defer_during_fullscreen();
+                        settings.max_fullscreen_deferral = local_max_fullscreen_deferral();
+                        settings.escalate_after = local_escalate_after();
+                        settings.fullscreen_after = local_fullscreen_after();
+                        settings.history_retention_days = local_history_retention_days();
+                        settings.custom_reminders = local_custom_reminders();
+                        settings.next_custom_id = local_next_custom_id();
+                        settings.schedule = local_schedule();
+                        // Drop rows that were added but never filled in
+                        settings.calendar_files = local_calendar_files()
+                            .into_iter()
+                            .filter(|path| !path.as_os_str().is_empty())
+                            .collect();
+                        settings.post_meeting_break = local_post_meeting_break();
+                        settings.post_meeting_break_duration = local_post_meeting_break_duration();
+                        settings.pomodoro = local_pomodoro();
+                        settings.adaptive = local_adaptive();
+                        settings.adaptive_log = local_adaptive_log();
+                        settings.delivery = local_delivery();
+                        settings.sound_muted = local_sound_muted();
+                        // One save and one broadcast for the whole form
+                        save_edits(&opened.read(), &settings);
+                        opened.set(settings);
+                        warning.set(config_warning());
                     },
                     "Save"
                 }
@@ -0,0 +1,400 @@
+use crate::export::{self, ExportError, ExportFormat};
+use crate::history::{HistoryEntry, HistoryQuery, HistoryStore};
+use crate::shared_state::get_settings;
+use crate::stats::{Statistics, Tally};
+use crate::STYLE;
+use chrono::{Local, NaiveDate, TimeDelta};
+use dioxus::prelude::*;
+use std::io;
+use std::path::PathBuf;
+
+/// Ranges offered above the charts, in days.
+const RANGES: [u32; 3] = [7, 30, 90];
+
+/// Height of the tallest bar, in CSS pixels.
+const CHART_HEIGHT: u32 = 120;
+
+/// History since `days` ago, or why it couldn't be read. Reads the whole
+/// file on the blocking pool.
+async fn load_history(days: u32) -> Result<Vec<HistoryEntry>, String> {
+    tokio::task::spawn_blocking(move || read_history(days))
+        .await
+        .unwrap_or_else(|err| Err(format!("Could not read the history: {err}")))
+}
+
+fn read_history(days: u32) -> Result<Vec<HistoryEntry>, String> {
+    let Some(store) = HistoryStore::open_default() else {
+        return Err("No data directory on this platform, so no history is kept.".to_string());
+    };
+    let today = Local::now().date_naive();
+    let from = today - TimeDelta::days(i64::from(days) - 1);
+    store
+        .query(&HistoryQuery::days(from, today))
+        .map_err(|err| format!("Could not read {}: {err}", store.path().display()))
+}
+
+/// Reminders shown versus completed per day and week, streaks, average
+/// break length and hour-of-day heatmaps, all from the local history.
+#[component]
+pub fn StatisticsWindow() -> Element {
+    let mut days = use_signal(|| RANGES[1]);
+    // Reloads whenever `days` changes, or on restart()
+    let mut history = use_resource(move || load_history(days()));
+
+    let today = Local::now().date_naive();
+    let from = today - TimeDelta::days(i64::from(days()) - 1);
+    let (stats, error) = match &*history.read() {
+        Some(Ok(entries)) => (Statistics::compute(entries, from, today), None),
+        Some(Err(err)) => (Statistics::compute(&[], from, today), Some(err.clone())),
+        None => (Statistics::compute(&[], from, today), None),
+    };
+    let loading = history.read().is_none();
+
+    // Types in the order Settings lists them, then any that were removed since
+    let settings = get_settings();
+    let order = settings.all_reminder_kinds();
+    let mut types = stats.types.clone();
+    types.sort_by_key(|row| {
+        order
+            .iter()
+            .position(|kind| kind.key() == row.kind.key())
+            .unwrap_or(usize::MAX)
+    });
+    let rows: Vec<(String, String, String, String, String)> = types
+        .iter()
+        .map(|row| {
+            (
+                settings.reminder_name(row.kind),
+                format!("{} / {}", row.total.completed, row.total.shown),
+                row.total.percent(),
+                format!("{} (best {})", row.current_streak, row.longest_streak),
+                row.average_break
+                    .map_or_else(|| "–".to_string(), format_seconds),
+            )
+        })
+        .collect();
+    let heatmap: Vec<(String, [Tally; 24])> = types
+        .iter()
+        .map(|row| (settings.reminder_name(row.kind), row.hours))
+        .collect();
+    let daily: Vec<(String, Tally)> = stats
+        .days
+        .iter()
+        .map(|day| (day.start.format("%b %-d").to_string(), day.tally))
+        .collect();
+    let weekly: Vec<(String, Tally)> = stats
+        .weeks
+        .iter()
+        .map(|week| {
+            let label = if week.is_whole_week() {
+                format!("Week of {}", week.start.format("%b %-d"))
+            } else {
+                format!(
+                    "{} – {}",
+                    week.start.format("%b %-d"),
+                    week.end.format("%b %-d")
+                )
+            };
+            (label, week.tally)
+        })
+        .collect();
+    let summary = format!(
+        "{} of {} reminders completed ({}). Streak: {} days, best {}.",
+        stats.total.completed,
+        stats.total.shown,
+        stats.total.percent(),
+        stats.current_streak,
+        stats.longest_streak
+    );
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: STYLE }
+        div {
+            class: "w-screen min-h-screen p-6 bg-gradient-to-br from-white to-blue-50",
+            div {
+                class: "bg-white p-5 rounded-xl shadow-lg flex flex-col gap-4",
+                div { class: "flex items-center justify-between",
+                    h2 { class: "text-2xl font-bold", "Statistics" }
+                    div { class: "flex items-center gap-2 text-sm",
+                        select {
+                            class: "px-1 border border-gray-200 rounded",
+                            onchange: move |e| {
+                                if let Ok(val) = e.value().parse() {
+                                    days.set(val);
+                                }
+                            },
+                            for range in RANGES {
+                                option {
+                                    value: "{range}",
+                                    selected: range == days(),
+                                    "Last {range} days"
+                                }
+                            }
+                        }
+                        button {
+                            class: "px-2 py-1 rounded bg-blue-700 text-white shadow hover:bg-blue-800",
+                            onclick: move |_| history.restart(),
+                            "Refresh"
+                        }
+                    }
+                }
+                if loading {
+                    p { class: "text-sm text-gray-400", "Loading the history…" }
+                }
+                if let Some(message) = error {
+                    div { class: "px-2 py-1 rounded bg-yellow-100 text-yellow-800 text-sm", "{message}" }
+                }
+                p { class: "text-base", "{summary}" }
+                div {
+                    h3 { class: "mb-1 text-base font-medium", "Per day" }
+                    BarChart { bars: daily }
+                }
+                div {
+                    h3 { class: "mb-1 text-base font-medium", "Per week" }
+                    BarChart { bars: weekly }
+                }
+                div {
+                    h3 { class: "mb-1 text-base font-medium", "By reminder type" }
+                    if rows.is_empty() {
+                        p { class: "text-sm text-gray-400", "No reminders recorded in this range." }
+                    } else {
+                        table { class: "w-full text-sm text-left",
+                            thead {
+                                tr { class: "text-gray-500",
+                                    th { "Reminder" }
+                                    th { "Completed" }
+                                    th { "Rate" }
+                                    th { "Streak (days)" }
+                                    th { "Average break" }
+                                }
+                            }
+                            tbody {
+                                for (name, completed, rate, streak, average) in rows {
+                                    tr {
+                                        td { "{name}" }
+                                        td { "{completed}" }
+                                        td { "{rate}" }
+                                        td { "{streak}" }
+                                        td { "{average}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if !heatmap.is_empty() {
+                    div {
+                        h3 { class: "mb-1 text-base font-medium", "Completed by hour of day" }
+                        Heatmap { rows: heatmap }
+                    }
+                }
+                ExportPanel { from, to: today }
+            }
+        }
+    }
+}
+
+/// Side-by-side bars of reminders shown (grey) and completed (blue).
+#[component]
+fn BarChart(bars: Vec<(String, Tally)>) -> Element {
+    let max = bars
+        .iter()
+        .map(|(_, tally)| tally.shown.max(tally.completed))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let bars: Vec<(String, u32, u32)> = bars
+        .into_iter()
+        .map(|(label, tally)| {
+            let title = format!(
+                "{label}: {} shown, {} completed ({})",
+                tally.shown,
+                tally.completed,
+                tally.percent()
+            );
+            (
+                title,
+                tally.shown * CHART_HEIGHT / max,
+                tally.completed * CHART_HEIGHT / max,
+            )
+        })
+        .collect();
+
+    rsx! {
+        div {
+            class: "flex items-end gap-px border-b border-gray-300",
+            style: "height: {CHART_HEIGHT}px;",
+            for (title, shown, completed) in bars {
+                div {
+                    class: "flex-1 flex items-end justify-center min-w-0",
+                    title: "{title}",
+                    div { class: "flex-1 bg-gray-300", style: "height: {shown}px;" }
+                    div { class: "flex-1 bg-blue-600", style: "height: {completed}px;" }
+                }
+            }
+        }
+    }
+}
+
+/// One row per reminder type and one column per hour, darker where more
+/// reminders were completed.
+#[component]
+fn Heatmap(rows: Vec<(String, [Tally; 24])>) -> Element {
+    let max = rows
+        .iter()
+        .flat_map(|(_, hours)| hours.iter().map(|tally| tally.completed))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let rows: Vec<(String, Vec<(String, f64)>)> = rows
+        .into_iter()
+        .map(|(name, hours)| {
+            let cells = hours
+                .iter()
+                .enumerate()
+                .map(|(hour, tally)| {
+                    let title = format!(
+                        "{hour:02}:00 – {} shown, {} completed",
+                        tally.shown, tally.completed
+                    );
+                    (title, f64::from(tally.completed) / f64::from(max))
+                })
+                .collect();
+            (name, cells)
+        })
+        .collect();
+
+    rsx! {
+        div { class: "grid gap-px text-xs", style: "grid-template-columns: 8rem repeat(24, minmax(0, 1fr));",
+            span {}
+            for hour in 0..24 {
+                span { class: "text-center text-gray-400",
+                    if hour % 6 == 0 { "{hour}" }
+                }
+            }
+            for (name, cells) in rows {
+                span { class: "truncate pr-1", "{name}" }
+                for (title, intensity) in cells {
+                    div {
+                        class: "h-4 rounded-sm border border-gray-100",
+                        title: "{title}",
+                        style: "background: rgba(37, 99, 235, {intensity});",
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Export of the history between two dates, starting out with the range
+/// shown in the charts.
+#[component]
+fn ExportPanel(from: NaiveDate, to: NaiveDate) -> Element {
+    let mut format = use_signal(ExportFormat::default);
+    let mut export_from = use_signal(|| from);
+    let mut export_to = use_signal(|| to);
+    let mut folder = use_signal(|| {
+        dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default()
+    });
+    let mut status = use_signal(|| None::<String>);
+    // A file that exists already, replaced if Export is clicked again
+    let mut replace = use_signal(|| None::<PathBuf>);
+    let mut exporting = use_signal(|| false);
+    let file_name = export::export_file_name(format(), export_from(), export_to());
+
+    rsx! {
+        div {
+            h3 { class: "mb-1 text-base font-medium", "Export" }
+            div { class: "flex flex-wrap items-center gap-2 text-sm",
+                select {
+                    class: "px-1 border border-gray-200 rounded",
+                    onchange: move |e| {
+                        let choice = e.value().parse::<usize>().ok().and_then(|index| ExportFormat::ALL.get(index));
+                        if let Some(&choice) = choice {
+                            format.set(choice);
+                        }
+                    },
+                    for (index, choice) in ExportFormat::ALL.into_iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: choice == format(),
+                            {choice.label()}
+                        }
+                    }
+                }
+                "from"
+                input {
+                    r#type: "date",
+                    class: "px-1 border border-gray-200 rounded",
+                    value: export_from().to_string(),
+                    oninput: move |e| if let Ok(val) = e.value().parse() { export_from.set(val); },
+                }
+                "to"
+                input {
+                    r#type: "date",
+                    class: "px-1 border border-gray-200 rounded",
+                    value: export_to().to_string(),
+                    oninput: move |e| if let Ok(val) = e.value().parse() { export_to.set(val); },
+                }
+                "into"
+                input {
+                    class: "flex-1 min-w-[12rem] px-1 border border-gray-200 rounded",
+                    placeholder: "/path/to/folder",
+                    value: folder().display().to_string(),
+                    oninput: move |e| folder.set(PathBuf::from(e.value())),
+                }
+                button {
+                    class: "px-2 py-1 rounded bg-blue-700 text-white shadow hover:bg-blue-800",
+                    title: "{file_name}",
+                    disabled: exporting(),
+                    onclick: move |_| {
+                        let (from, to) = (export_from(), export_to());
+                        if from > to {
+                            status.set(Some("The first day is after the last one.".to_string()));
+                            return;
+                        }
+                        let path = folder().join(export::export_file_name(format(), from, to));
+                        let confirmed = replace.take().as_ref() == Some(&path);
+                        let (chosen, settings) = (format(), get_settings());
+                        exporting.set(true);
+                        status.set(Some("Exporting…".to_string()));
+                        // Reads the whole history, so keep it off the UI thread
+                        spawn(async move {
+                            let target = path.clone();
+                            let result = tokio::task::spawn_blocking(move || {
+                                export::export_history_to_file(&target, chosen, from, to, &settings, confirmed)
+                            })
+                            .await
+                            .unwrap_or_else(|err| Err(ExportError::Io(io::Error::other(err))));
+                            exporting.set(false);
+                            status.set(Some(match result {
+                                Ok(count) => format!("Exported {count} records to {}.", path.display()),
+                                Err(ExportError::Io(err)) if err.kind() == io::ErrorKind::AlreadyExists => {
+                                    let message = format!("{} already exists. Click Export again to replace it.", path.display());
+                                    replace.set(Some(path));
+                                    message
+                                }
+                                Err(err) => format!("{}: {err}", path.display()),
+                            }));
+                        });
+                    },
+                    "Export"
+                }
+            }
+            if let Some(message) = status() {
+                p { class: "mt-1 text-sm text-gray-600", "{message}" }
+            }
+        }
+    }
+}
+
+/// Seconds as e.g. "45 s" or "5 min 20 s".
+fn format_seconds(seconds: u64) -> String {
+    match (seconds / 60, seconds % 60) {
+        (0, seconds) => format!("{seconds} s"),
+        (minutes, 0) => format!("{minutes} min"),
+        (minutes, seconds) => format!("{minutes} min {seconds} s"),
+    }
+}
@@ -0,0 +1,195 @@
+use crate::shared_state::BlinkSettings;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const APP_DIR: &str = "blinkion";
+const FILE_NAME: &str = "settings.toml";
+
+/// Why the settings file could not be used.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "could not access settings file: {err}"),
+            ConfigError::Parse(err) => write!(f, "settings file is malformed: {err}"),
+            ConfigError::Serialize(err) => write!(f, "could not serialize settings: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/blinkion/settings.toml` (or the platform equivalent).
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR).join(FILE_NAME))
+}
+
+/// Read settings from `path`. A missing file is not an error and yields `None`.
+/// Intervals of zero are raised to one second.
+pub fn load_from(path: &Path) -> Result<Option<BlinkSettings>, ConfigError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut settings: BlinkSettings = toml::from_str(&text).map_err(ConfigError::Parse)?;
+    settings.clamp_intervals();
+    Ok(Some(settings))
+}
+
+/// Write settings to `path` via a temporary file and rename, so a crash
+/// mid-write never leaves a truncated config behind.
+pub fn save_to(path: &Path, settings: &BlinkSettings) -> Result<(), ConfigError> {
+    let text = toml::to_string_pretty(settings).map_err(ConfigError::Serialize)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("toml.tmp");
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(text.as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Load settings from the default location, falling back to defaults.
+///
+/// Returns the settings together with a warning to show the user when the
+/// file existed but could not be used.
+pub fn load_or_default() -> (BlinkSettings, Option<String>) {
+    match config_path() {
+        Some(path) => load_or_back_up(&path),
+        None => (BlinkSettings::default(), None),
+    }
+}
+
+/// Load settings from the default location without ever touching the file,
+/// falling back to defaults. For one-off commands such as `--export`, which
+/// leave a malformed file for the app to report and back up.
+pub fn load_read_only() -> BlinkSettings {
+    config_path()
+        .map(|path| load_read_only_from(&path))
+        .unwrap_or_default()
+}
+
+fn load_read_only_from(path: &Path) -> BlinkSettings {
+    load_from(path)
+        .unwrap_or_else(|err| {
+            eprintln!(
+                "Warning: {}: {err}. Using default settings.",
+                path.display()
+            );
+            None
+        })
+        .unwrap_or_default()
+}
+
+/// Load settings from `path`, falling back to defaults. A malformed file is
+/// moved aside to `settings.toml.bak` first, so that saving the defaults
+/// doesn't destroy the user's edits.
+fn load_or_back_up(path: &Path) -> (BlinkSettings, Option<String>) {
+    let err = match load_from(path) {
+        Ok(settings) => return (settings.unwrap_or_default(), None),
+        Err(err) => err,
+    };
+    let mut warning = format!("{}: {err}. Using default settings.", path.display());
+    if let ConfigError::Parse(_) = err {
+        let backup = path.with_extension("toml.bak");
+        match fs::rename(path, &backup) {
+            Ok(()) => warning.push_str(&format!(" The file was moved to {}.", backup.display())),
+            Err(err) => warning.push_str(&format!(" Could not back it up: {err}.")),
+        }
+    }
+    eprintln!("Warning: {warning}");
+    (BlinkSettings::default(), Some(warning))
+}
+
+/// Save settings to the default location.
+pub fn save(settings: &BlinkSettings) -> Result<(), ConfigError> {
+    match config_path() {
+        Some(path) => save_to(&path, settings),
+        None => Err(ConfigError::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no config directory on this platform",
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory for one test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("blinkion-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn settings_survive_a_round_trip() {
+        let path = scratch_dir("round-trip").join(FILE_NAME);
+        let settings = BlinkSettings {
+            blink_interval: 42,
+            ..Default::default()
+        };
+        save_to(&path, &settings).unwrap();
+        let loaded = load_from(&path).unwrap().unwrap();
+        assert_eq!(loaded.blink_interval, 42);
+    }
+
+    #[test]
+    fn zero_intervals_are_raised() {
+        let path = scratch_dir("zero").join(FILE_NAME);
+        fs::write(&path, "blink_interval = 0\n").unwrap();
+        assert_eq!(load_from(&path).unwrap().unwrap().blink_interval, 1);
+    }
+
+    #[test]
+    fn malformed_file_is_backed_up_before_defaults_are_used() {
+        let path = scratch_dir("malformed").join(FILE_NAME);
+        fs::write(&path, "blink_interval = \"soon\"\n").unwrap();
+        let (settings, warning) = load_or_back_up(&path);
+        assert_eq!(
+            settings.blink_interval,
+            BlinkSettings::default().blink_interval
+        );
+        assert!(warning.unwrap().contains("settings.toml.bak"));
+        assert!(!path.exists());
+        let backup = fs::read_to_string(path.with_extension("toml.bak")).unwrap();
+        assert_eq!(backup, "blink_interval = \"soon\"\n");
+    }
+
+    #[test]
+    fn read_only_loading_leaves_a_malformed_file_alone() {
+        let path = scratch_dir("read-only").join(FILE_NAME);
+        fs::write(&path, "blink_interval = \"soon\"\n").unwrap();
+        let settings = load_read_only_from(&path);
+        assert_eq!(
+            settings.blink_interval,
+            BlinkSettings::default().blink_interval
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "blink_interval = \"soon\"\n"
+        );
+        assert!(!path.with_extension("toml.bak").exists());
+    }
+}
@@ -0,0 +1,233 @@
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Reports whether the desktop is in Do Not Disturb mode.
+pub trait DndSource {
+    /// Whether notifications are silenced, updated whenever that changes.
+    /// `false` if it cannot be determined.
+    fn watch(&self) -> watch::Receiver<bool>;
+}
+
+/// Fallback for desktops without a Do Not Disturb flag: never silenced.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoDndSource;
+
+impl DndSource for NoDndSource {
+    fn watch(&self) -> watch::Receiver<bool> {
+        watch::channel(false).1
+    }
+}
+
+/// Watches Do Not Disturb on the session bus.
+///
+/// KDE (and other servers following the freedesktop notifications spec 1.3)
+/// expose it as the `Inhibited` property of `org.freedesktop.Notifications`.
+/// GNOME Shell doesn't, so this falls back to its `show-banners` setting as
+/// published by the settings portal. Either way a task follows the change
+/// signals, so nothing is polled.
+#[cfg(target_os = "linux")]
+pub struct DbusDndSource {
+    state: watch::Sender<bool>,
+}
+
+#[cfg(target_os = "linux")]
+impl DbusDndSource {
+    /// Connect to the session bus. Returns `None` if there is none, or
+    /// nothing on it publishes Do Not Disturb.
+    pub async fn connect() -> Option<Self> {
+        Self::with_connection(zbus::Connection::session().await.ok()?).await
+    }
+
+    /// Watch the bus behind `conn`, e.g. a private bus standing in for the
+    /// session bus. Must be called from within a tokio runtime.
+    pub async fn with_connection(conn: zbus::Connection) -> Option<Self> {
+        use futures_util::StreamExt;
+
+        let state = watch::Sender::new(false);
+        let notifications = zbus::Proxy::new(
+            &conn,
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+        )
+        .await
+        .ok()?;
+        if let Ok(inhibited) = notifications.get_property::<bool>("Inhibited").await {
+            state.send_replace(inhibited);
+            let mut changes = notifications
+                .receive_property_changed::<bool>("Inhibited")
+                .await;
+            let watched = state.clone();
+            tokio::spawn(async move {
+                while let Some(change) = changes.next().await {
+                    if let Ok(inhibited) = change.get().await {
+                        watched.send_replace(inhibited);
+                    }
+                }
+            });
+            return Some(Self { state });
+        }
+
+        let portal = zbus::Proxy::new(
+            &conn,
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+        )
+        .await
+        .ok()?;
+        let setting = ("org.gnome.desktop.notifications", "show-banners");
+        let mut changes = portal
+            .receive_signal_with_args("SettingChanged", &[(0, setting.0), (1, setting.1)])
+            .await
+            .ok()?;
+        let value: zbus::zvariant::OwnedValue = portal.call("Read", &setting).await.ok()?;
+        state.send_replace(!show_banners(&value)?);
+        let watched = state.clone();
+        tokio::spawn(async move {
+            while let Some(message) = changes.next().await {
+                let changed = message
+                    .body()
+                    .deserialize::<(String, String, zbus::zvariant::OwnedValue)>();
+                if let Some(show) = changed.ok().and_then(|(_, _, value)| show_banners(&value)) {
+                    watched.send_replace(!show);
+                }
+            }
+        });
+        Some(Self { state })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl DndSource for DbusDndSource {
+    fn watch(&self) -> watch::Receiver<bool> {
+        self.state.subscribe()
+    }
+}
+
+/// The `show-banners` flag in a settings portal value. `Read` wraps the
+/// setting in one variant more than it declares; `SettingChanged` doesn't.
+#[cfg(target_os = "linux")]
+fn show_banners(value: &zbus::zvariant::Value) -> Option<bool> {
+    match value {
+        zbus::zvariant::Value::Value(inner) => bool::try_from(&**inner).ok(),
+        value => bool::try_from(value).ok(),
+    }
+}
+
+/// The best Do Not Disturb source available on this machine.
+pub async fn system_dnd_source() -> Arc<dyn DndSource + Send + Sync> {
+    #[cfg(target_os = "linux")]
+    if let Some(source) = DbusDndSource::connect().await {
+        return Arc::new(source);
+    }
+    Arc::new(NoDndSource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reminder::{DeliverySettings, ReminderType};
+    use crate::scheduler::{FakeClock, ReminderScheduler};
+    use crate::shared_state::BlinkSettings;
+    use crate::test_support::{kinds, settings, SECOND};
+
+    #[test]
+    fn popups_are_queued_until_dnd_ends() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(
+            clock.clone(),
+            &BlinkSettings {
+                posture_interval: 60,
+                ..settings()
+            },
+        );
+        scheduler.observe_dnd(true);
+        clock.advance(3600 * SECOND);
+        assert!(scheduler.poll().is_empty());
+        assert_eq!(
+            scheduler.held_back(),
+            [ReminderType::Blink, ReminderType::Posture]
+        );
+
+        scheduler.observe_dnd(false);
+        assert_eq!(
+            kinds(scheduler.poll()),
+            [ReminderType::Blink, ReminderType::Posture]
+        );
+    }
+
+    #[test]
+    fn types_that_ignore_dnd_still_fire() {
+        let clock = FakeClock::default();
+        let mut settings = BlinkSettings {
+            posture_interval: 30,
+            ..settings()
+        };
+        settings.delivery.insert(
+            ReminderType::Posture.key(),
+            DeliverySettings {
+                respect_dnd: false,
+                ..Default::default()
+            },
+        );
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings);
+        scheduler.observe_dnd(true);
+        clock.advance(30 * SECOND);
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::Posture]);
+        assert_eq!(scheduler.held_back(), [ReminderType::Blink]);
+    }
+
+    /// A notification server that only knows about Do Not Disturb.
+    #[cfg(target_os = "linux")]
+    struct Notifications {
+        inhibited: bool,
+    }
+
+    #[cfg(target_os = "linux")]
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl Notifications {
+        #[zbus(property)]
+        fn inhibited(&self) -> bool {
+            self.inhibited
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn follows_the_inhibited_property_on_the_bus() {
+        let (server, client) = std::os::unix::net::UnixStream::pair().unwrap();
+        let path = "/org/freedesktop/Notifications";
+        let server = zbus::connection::Builder::unix_stream(server)
+            .server(zbus::Guid::generate())
+            .unwrap()
+            .p2p()
+            .serve_at(path, Notifications { inhibited: true })
+            .unwrap()
+            .build();
+        let client = zbus::connection::Builder::unix_stream(client).p2p().build();
+        let (server, client) = futures_util::try_join!(server, client).unwrap();
+
+        let source = DbusDndSource::with_connection(client).await.unwrap();
+        let mut dnd = source.watch();
+        assert!(*dnd.borrow_and_update());
+
+        let iface = server
+            .object_server()
+            .interface::<_, Notifications>(path)
+            .await
+            .unwrap();
+        iface.get_mut().await.inhibited = false;
+        iface
+            .get()
+            .await
+            .inhibited_changed(iface.signal_emitter())
+            .await
+            .unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(5), dnd.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!*dnd.borrow());
+    }
+}
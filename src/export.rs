@@ -0,0 +1,541 @@
+use crate::history::{HistoryEntry, HistoryQuery, HistoryStore, LifecycleEvent};
+use crate::shared_state::BlinkSettings;
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// How far back `--export` reaches when `--from` is not given, in days.
+const DEFAULT_EXPORT_DAYS: i64 = 30;
+
+/// Usage of the export flags, printed on a bad command line.
+pub const USAGE: &str = "\
+Usage: blinkion [--export csv|json|ics] [--from YYYY-MM-DD] [--to YYYY-MM-DD] [--output PATH]
+
+  --export FORMAT  Write the reminder history to PATH (or standard output) and exit
+  --from DATE      First day to export (default: 30 days ago)
+  --to DATE        Last day to export (default: today)
+  --output PATH    File to write instead of standard output";
+
+/// File formats the reminder history can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// One row per history entry.
+    #[default]
+    Csv,
+    /// The range and every entry, as one JSON document.
+    Json,
+    /// iCalendar, with one event per completed break.
+    Ics,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Ics];
+
+    /// Name shown in the statistics window.
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Ics => "iCalendar (.ics)",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ics => "ics",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ExportFormat::ALL
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown export format \"{s}\", expected csv, json or ics"))
+    }
+}
+
+/// A history entry as it appears in CSV and JSON exports.
+#[derive(Debug, Clone, Serialize)]
+struct ExportedEntry {
+    at: DateTime<Local>,
+    /// [`ReminderType::key`](crate::reminder::ReminderType::key).
+    reminder: String,
+    /// What the user calls the reminder.
+    name: String,
+    event: LifecycleEvent,
+    duration_seconds: u64,
+}
+
+/// The whole JSON export.
+#[derive(Debug, Serialize)]
+struct JsonExport<'a> {
+    from: NaiveDate,
+    to: NaiveDate,
+    exported_at: DateTime<Local>,
+    entries: &'a [ExportedEntry],
+}
+
+/// Write the `entries` recorded from `from` to `to` as `format`, naming
+/// reminders as `settings` does. Returns how many entries (for `.ics`,
+/// events) were written.
+pub fn write_export(
+    out: &mut impl Write,
+    format: ExportFormat,
+    entries: &[HistoryEntry],
+    from: NaiveDate,
+    to: NaiveDate,
+    settings: &BlinkSettings,
+) -> io::Result<usize> {
+    let exported: Vec<ExportedEntry> = entries
+        .iter()
+        .map(|entry| ExportedEntry {
+            at: entry.at,
+            reminder: entry.kind.key(),
+            name: settings.reminder_name(entry.kind),
+            event: entry.event,
+            duration_seconds: entry.duration,
+        })
+        .collect();
+    match format {
+        ExportFormat::Csv => write_csv(out, &exported),
+        ExportFormat::Json => {
+            let export = JsonExport {
+                from,
+                to,
+                exported_at: Local::now(),
+                entries: &exported,
+            };
+            serde_json::to_writer_pretty(&mut *out, &export).map_err(io::Error::other)?;
+            writeln!(out)?;
+            Ok(exported.len())
+        }
+        ExportFormat::Ics => write_ics(out, &exported),
+    }
+}
+
+fn write_csv(out: &mut impl Write, entries: &[ExportedEntry]) -> io::Result<usize> {
+    writeln!(out, "timestamp,reminder,name,event,duration_seconds")?;
+    for entry in entries {
+        // Serialized the same way as in JSON, e.g. "auto_closed"
+        let event = serde_json::to_value(entry.event).map_err(io::Error::other)?;
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            entry.at.to_rfc3339(),
+            csv_field(&entry.reminder),
+            csv_field(&entry.name),
+            event.as_str().unwrap_or_default(),
+            entry.duration_seconds
+        )?;
+    }
+    Ok(entries.len())
+}
+
+/// Quote `value` if it contains a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One `VEVENT` per completed reminder, ending when it was answered. The
+/// events are marked free so that importing them into a calendar Blinkion
+/// watches doesn't hold back the next reminders.
+fn write_ics(out: &mut impl Write, entries: &[ExportedEntry]) -> io::Result<usize> {
+    let stamp = ics_time(Local::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Blinkion//Reminder history//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    let mut count = 0;
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.event == LifecycleEvent::Acknowledged)
+    {
+        // Even an instant "Done" gets a visible one-second event
+        let length = TimeDelta::seconds(entry.duration_seconds.max(1) as i64);
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!(
+                "UID:{}-{}@blinkion",
+                entry.at.timestamp_millis(),
+                entry.reminder
+            ),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART:{}", ics_time(entry.at - length)),
+            format!("DTEND:{}", ics_time(entry.at)),
+            format!("SUMMARY:{}", ics_text(&entry.name)),
+            "CATEGORIES:Break".to_string(),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+        count += 1;
+    }
+    lines.push("END:VCALENDAR".to_string());
+    for line in lines {
+        write!(out, "{}\r\n", fold_ics_line(&line))?;
+    }
+    Ok(count)
+}
+
+/// UTC date-time in iCalendar form, e.g. `20261018T093000Z`.
+fn ics_time(at: DateTime<Local>) -> String {
+    at.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape `value` for an iCalendar TEXT property.
+fn ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Break `line` into lines of at most 75 bytes, continued with a leading
+/// space, without splitting a character.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+/// Default file name for an export, e.g. `blinkion-history-2026-09-19-2026-10-18.csv`.
+pub fn export_file_name(format: ExportFormat, from: NaiveDate, to: NaiveDate) -> String {
+    format!("blinkion-history-{from}-{to}.{}", format.extension())
+}
+
+/// Why an export could not be written.
+#[derive(Debug)]
+pub enum ExportError {
+    /// The platform has no data directory, so there is no history.
+    NoHistory,
+    Io(io::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::NoHistory => write!(f, "no data directory, so no history is kept"),
+            ExportError::Io(err) => write!(f, "could not export history: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+/// Read the history from the default store and write the days `from` to
+/// `to` to `out`.
+pub fn export_history(
+    out: &mut impl Write,
+    format: ExportFormat,
+    from: NaiveDate,
+    to: NaiveDate,
+    settings: &BlinkSettings,
+) -> Result<usize, ExportError> {
+    let store = HistoryStore::open_default().ok_or(ExportError::NoHistory)?;
+    let entries = store.query(&HistoryQuery::days(from, to))?;
+    Ok(write_export(out, format, &entries, from, to, settings)?)
+}
+
+/// Like [`export_history`], into a file at `path`. An existing file is only
+/// replaced if `replace` is set, and otherwise fails the export with
+/// [`io::ErrorKind::AlreadyExists`]. The export is written to a temporary
+/// file next to `path` and renamed into place, like the settings, so a
+/// failed export never leaves a truncated file behind.
+/// Reads the whole history, so call it from a blocking task.
+pub fn export_history_to_file(
+    path: &Path,
+    format: ExportFormat,
+    from: NaiveDate,
+    to: NaiveDate,
+    settings: &BlinkSettings,
+    replace: bool,
+) -> Result<usize, ExportError> {
+    if !replace && path.try_exists()? {
+        return Err(io::Error::from(io::ErrorKind::AlreadyExists).into());
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let result = write_to_file(&tmp, |out| export_history(out, format, from, to, settings))
+        .and_then(|count| {
+            fs::rename(&tmp, path)?;
+            Ok(count)
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Create the file at `path` and fill it with `write`.
+fn write_to_file(
+    path: &Path,
+    write: impl FnOnce(&mut io::BufWriter<fs::File>) -> Result<usize, ExportError>,
+) -> Result<usize, ExportError> {
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    let count = write(&mut file)?;
+    file.into_inner()
+        .map_err(io::IntoInnerError::into_error)?
+        .sync_all()?;
+    Ok(count)
+}
+
+/// An export asked for on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportRequest {
+    pub format: ExportFormat,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Standard output if `None`.
+    pub output: Option<PathBuf>,
+}
+
+impl ExportRequest {
+    /// Parse the command-line arguments after the program name. `Ok(None)`
+    /// means no export was asked for and the app should start as usual.
+    pub fn from_args(
+        args: impl IntoIterator<Item = String>,
+        today: NaiveDate,
+    ) -> Result<Option<Self>, String> {
+        let mut format = None;
+        let mut from = None;
+        let mut to = None;
+        let mut output = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+            match arg.as_str() {
+                "--export" => format = Some(value()?.parse()?),
+                "--from" => from = Some(parse_date(&value()?)?),
+                "--to" => to = Some(parse_date(&value()?)?),
+                "--output" => output = Some(PathBuf::from(value()?)),
+                _ => return Err(format!("unexpected argument \"{arg}\"")),
+            }
+        }
+        let Some(format) = format else {
+            if from.is_some() || to.is_some() || output.is_some() {
+                return Err("--from, --to and --output only apply to --export".to_string());
+            }
+            return Ok(None);
+        };
+        let to = to.unwrap_or(today);
+        let from = from.unwrap_or(to - TimeDelta::days(DEFAULT_EXPORT_DAYS - 1));
+        if from > to {
+            return Err(format!("--from {from} is after --to {to}"));
+        }
+        Ok(Some(Self {
+            format,
+            from,
+            to,
+            output,
+        }))
+    }
+
+    /// Write the export, to the output file or standard output. Like a shell
+    /// redirect, `--output` replaces an existing file.
+    pub fn run(&self, settings: &BlinkSettings) -> Result<usize, ExportError> {
+        match &self.output {
+            Some(path) => {
+                export_history_to_file(path, self.format, self.from, self.to, settings, true)
+            }
+            None => {
+                let mut stdout = io::stdout().lock();
+                let count = export_history(&mut stdout, self.format, self.from, self.to, settings)?;
+                stdout.flush()?;
+                Ok(count)
+            }
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("\"{value}\" is not a date like 2026-10-18"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reminder::ReminderType;
+    use chrono::TimeZone;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn parse(args: &[&str]) -> Result<Option<ExportRequest>, String> {
+        ExportRequest::from_args(args.iter().map(|arg| arg.to_string()), date(18))
+    }
+
+    #[test]
+    fn export_flags_are_parsed() {
+        assert_eq!(parse(&[]), Ok(None));
+        assert_eq!(
+            parse(&[
+                "--export",
+                "ICS",
+                "--from",
+                "2026-10-01",
+                "--output",
+                "out.ics"
+            ]),
+            Ok(Some(ExportRequest {
+                format: ExportFormat::Ics,
+                from: date(1),
+                to: date(18),
+                output: Some(PathBuf::from("out.ics")),
+            }))
+        );
+        let request = parse(&["--export", "csv"]).unwrap().unwrap();
+        assert_eq!(
+            (request.from, request.to),
+            (date(18) - TimeDelta::days(29), date(18))
+        );
+    }
+
+    #[test]
+    fn bad_export_flags_are_rejected() {
+        for (args, error) in [
+            (&["--export"][..], "--export needs a value"),
+            (
+                &["--export", "pdf"],
+                "unknown export format \"pdf\", expected csv, json or ics",
+            ),
+            (
+                &["--export", "csv", "--to", "18.10.2026"],
+                "\"18.10.2026\" is not a date like 2026-10-18",
+            ),
+            (
+                &[
+                    "--export",
+                    "csv",
+                    "--from",
+                    "2026-10-18",
+                    "--to",
+                    "2026-10-01",
+                ],
+                "--from 2026-10-18 is after --to 2026-10-01",
+            ),
+            (
+                &["--from", "2026-10-01"],
+                "--from, --to and --output only apply to --export",
+            ),
+            (&["--verbose"], "unexpected argument \"--verbose\""),
+        ] {
+            assert_eq!(parse(args), Err(error.to_string()), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("Stretch"), "Stretch");
+        assert_eq!(csv_field("Stretch, then walk"), "\"Stretch, then walk\"");
+        assert_eq!(csv_field("The \"big\" break"), "\"The \"\"big\"\" break\"");
+        assert_eq!(csv_field("Two\nlines"), "\"Two\nlines\"");
+    }
+
+    #[test]
+    fn ics_lines_fold_at_75_bytes_between_characters() {
+        assert_eq!(fold_ics_line("SUMMARY:Short"), "SUMMARY:Short");
+        // Two-byte characters, with one straddling byte 75
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        let folded = fold_ics_line(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 74);
+        assert!(lines[1].starts_with(' '));
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn ics_export_only_has_completed_breaks() {
+        let entry = |hour, event| HistoryEntry {
+            at: Local.with_ymd_and_hms(2026, 10, 18, hour, 0, 0).unwrap(),
+            kind: ReminderType::EyeBreak,
+            event,
+            duration: 20,
+        };
+        let entries = [
+            entry(9, LifecycleEvent::Scheduled),
+            entry(10, LifecycleEvent::Shown),
+            entry(10, LifecycleEvent::Acknowledged),
+            entry(11, LifecycleEvent::Shown),
+            entry(11, LifecycleEvent::Skipped),
+        ];
+        let mut out = Vec::new();
+        let count = write_export(
+            &mut out,
+            ExportFormat::Ics,
+            &entries,
+            date(18),
+            date(18),
+            &BlinkSettings::default(),
+        )
+        .unwrap();
+        let ics = String::from_utf8(out).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains(&format!("DTEND:{}", ics_time(entries[2].at))));
+    }
+
+    #[test]
+    fn existing_files_are_replaced_only_when_asked() {
+        let dir = std::env::temp_dir().join(format!("blinkion-export-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.csv");
+        fs::write(&path, "kept").unwrap();
+        let export = |replace| {
+            export_history_to_file(
+                &path,
+                ExportFormat::Csv,
+                date(1),
+                date(18),
+                &BlinkSettings::default(),
+                replace,
+            )
+        };
+
+        match export(false) {
+            Err(ExportError::Io(err)) => assert_eq!(err.kind(), io::ErrorKind::AlreadyExists),
+            other => panic!("expected AlreadyExists, got {other:?}"),
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "kept");
+
+        export(true).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        assert!(csv.starts_with("timestamp,reminder,"), "{csv}");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often to check whether a fullscreen app has gone away.
+pub const FOCUS_RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reports whether the window the user is looking at covers the whole screen,
+/// e.g. a presentation, a video or a screen-shared call.
+pub trait FocusGuard {
+    /// Whether the active window is fullscreen. `false` if it cannot be determined.
+    fn is_fullscreen(&self) -> bool;
+}
+
+/// Fallback for platforms without fullscreen detection: never fullscreen.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoFocusGuard;
+
+impl FocusGuard for NoFocusGuard {
+    fn is_fullscreen(&self) -> bool {
+        false
+    }
+}
+
+/// Checks `_NET_WM_STATE_FULLSCREEN` on the EWMH `_NET_ACTIVE_WINDOW`.
+#[cfg(target_os = "linux")]
+pub struct X11FocusGuard {
+    conn: x11rb::rust_connection::RustConnection,
+    root: x11rb::protocol::xproto::Window,
+    net_active_window: x11rb::protocol::xproto::Atom,
+    net_wm_state: x11rb::protocol::xproto::Atom,
+    net_wm_state_fullscreen: x11rb::protocol::xproto::Atom,
+}
+
+#[cfg(target_os = "linux")]
+impl X11FocusGuard {
+    /// Connect to `$DISPLAY`. Returns `None` if there is no X server.
+    pub fn connect() -> Option<Self> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::xproto::ConnectionExt;
+
+        let (conn, screen_num) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen_num)?.root;
+        let atom = |name: &[u8]| Some(conn.intern_atom(false, name).ok()?.reply().ok()?.atom);
+        let net_active_window = atom(b"_NET_ACTIVE_WINDOW")?;
+        let net_wm_state = atom(b"_NET_WM_STATE")?;
+        let net_wm_state_fullscreen = atom(b"_NET_WM_STATE_FULLSCREEN")?;
+        Some(Self {
+            conn,
+            root,
+            net_active_window,
+            net_wm_state,
+            net_wm_state_fullscreen,
+        })
+    }
+
+    fn active_window_is_fullscreen(&self) -> Option<bool> {
+        use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+        let active = self
+            .conn
+            .get_property(
+                false,
+                self.root,
+                self.net_active_window,
+                AtomEnum::WINDOW,
+                0,
+                1,
+            )
+            .ok()?
+            .reply()
+            .ok()?
+            .value32()?
+            .next()
+            .filter(|window| *window != x11rb::NONE)?;
+        let state = self
+            .conn
+            .get_property(false, active, self.net_wm_state, AtomEnum::ATOM, 0, 64)
+            .ok()?
+            .reply()
+            .ok()?;
+        let fullscreen = state
+            .value32()?
+            .any(|atom| atom == self.net_wm_state_fullscreen);
+        Some(fullscreen)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl FocusGuard for X11FocusGuard {
+    fn is_fullscreen(&self) -> bool {
+        self.active_window_is_fullscreen().unwrap_or(false)
+    }
+}
+
+/// A focus guard whose answer is set by hand. Clones share the same value.
+/// The best focus guard available on this machine. Connecting and asking
+/// both block on the X server, so do it from a blocking task.
+pub fn system_focus_guard() -> Arc<dyn FocusGuard + Send + Sync> {
+    #[cfg(target_os = "linux")]
+    if let Some(guard) = X11FocusGuard::connect() {
+        return Arc::new(guard);
+    }
+    Arc::new(NoFocusGuard)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reminder::ReminderType;
+    use crate::scheduler::{FakeClock, ReminderScheduler};
+    use crate::shared_state::BlinkSettings;
+    use crate::test_support::{self, kinds, SECOND};
+
+    fn settings() -> BlinkSettings {
+        BlinkSettings {
+            defer_during_fullscreen: true,
+            max_fullscreen_deferral: 60,
+            ..test_support::settings()
+        }
+    }
+
+    #[test]
+    fn popups_wait_for_fullscreen_to_end() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        clock.advance(30 * SECOND);
+        scheduler.observe_fullscreen(true);
+        assert!(scheduler.poll().is_empty());
+        assert_eq!(scheduler.held_back(), [ReminderType::Blink]);
+
+        clock.advance(10 * SECOND);
+        scheduler.observe_fullscreen(false);
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::Blink]);
+        assert!(scheduler.held_back().is_empty());
+    }
+
+    #[test]
+    fn popups_are_held_back_no_longer_than_the_maximum() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        clock.advance(89 * SECOND);
+        scheduler.observe_fullscreen(true);
+        assert!(scheduler.poll().is_empty());
+        clock.advance(SECOND);
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::Blink]);
+    }
+
+    #[test]
+    fn fullscreen_is_ignored_when_deferral_is_off() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(
+            clock.clone(),
+            &BlinkSettings {
+                defer_during_fullscreen: false,
+                ..settings()
+            },
+        );
+        clock.advance(30 * SECOND);
+        scheduler.observe_fullscreen(true);
+        assert!(!scheduler.is_fullscreen());
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::Blink]);
+    }
+}
@@ -0,0 +1,535 @@
+use crate::reminder::{ReminderOutcome, ReminderType};
+use crate::scheduler::{Countdown, ReminderEvent};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+
+const APP_DIR: &str = "blinkion";
+const FILE_NAME: &str = "history.jsonl";
+
+/// How often old entries are purged while the app keeps running.
+const PURGE_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// Something that happened to a reminder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    /// A countdown started.
+    Scheduled,
+    /// The reminder was delivered.
+    Shown,
+    /// The user answered "Done", or sat through a break countdown.
+    Acknowledged,
+    Snoozed,
+    Skipped,
+    /// The reminder went away without an answer.
+    AutoClosed,
+    /// The reminder came due but was held back by a fullscreen app, a
+    /// meeting or Do Not Disturb.
+    Suppressed,
+}
+
+impl LifecycleEvent {
+    pub const ALL: [LifecycleEvent; 7] = [
+        LifecycleEvent::Scheduled,
+        LifecycleEvent::Shown,
+        LifecycleEvent::Acknowledged,
+        LifecycleEvent::Snoozed,
+        LifecycleEvent::Skipped,
+        LifecycleEvent::AutoClosed,
+        LifecycleEvent::Suppressed,
+    ];
+
+    /// The event recording how the user answered a reminder.
+    pub fn answered(outcome: ReminderOutcome) -> Self {
+        match outcome {
+            ReminderOutcome::Done => LifecycleEvent::Acknowledged,
+            ReminderOutcome::Snoozed => LifecycleEvent::Snoozed,
+            ReminderOutcome::Skipped => LifecycleEvent::Skipped,
+            ReminderOutcome::Ignored => LifecycleEvent::AutoClosed,
+        }
+    }
+}
+
+/// One line of the history file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub at: DateTime<Local>,
+    pub kind: ReminderType,
+    pub event: LifecycleEvent,
+    /// Seconds, depending on `event`: until the reminder comes due when
+    /// scheduled, how long it was meant to stay up when shown, how long it
+    /// was up when answered, and how long it was held back when suppressed.
+    pub duration: u64,
+}
+
+/// Which entries a [`HistoryStore::query`] returns. Empty lists match
+/// everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryQuery {
+    /// Only entries at or after this time.
+    pub since: Option<DateTime<Local>>,
+    /// Only entries before this time.
+    pub until: Option<DateTime<Local>>,
+    pub kinds: Vec<ReminderType>,
+    pub events: Vec<LifecycleEvent>,
+}
+
+impl HistoryQuery {
+    /// Every entry recorded on the local days `from` to `to`, both included.
+    pub fn days(from: NaiveDate, to: NaiveDate) -> Self {
+        let midnight = |day: NaiveDate| {
+            day.and_time(NaiveTime::MIN)
+                .and_local_timezone(Local)
+                .earliest()
+        };
+        Self {
+            since: midnight(from),
+            until: to.succ_opt().and_then(midnight),
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.since.is_none_or(|since| entry.at >= since)
+            && self.until.is_none_or(|until| entry.at < until)
+            && (self.kinds.is_empty() || self.kinds.contains(&entry.kind))
+            && (self.events.is_empty() || self.events.contains(&entry.event))
+    }
+}
+
+/// `$XDG_DATA_HOME/blinkion/history.jsonl` (or the platform equivalent).
+pub fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR).join(FILE_NAME))
+}
+
+/// Held while writing the history file, so that an entry appended during a
+/// purge isn't lost when the purged copy replaces the file.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Append-only reminder history, one JSON object per line.
+///
+/// Lines that can't be parsed, such as one cut short by a crash, are
+/// skipped when reading.
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The store at [`history_path`], or `None` if the platform has no data
+    /// directory.
+    pub fn open_default() -> Option<Self> {
+        history_path().map(Self::new)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Add `entry` to the end of the file, creating it if needed.
+    pub fn append(&self, entry: &HistoryEntry) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut line = serde_json::to_string(entry).map_err(io::Error::other)?;
+        line.push('\n');
+        let _guard = WRITE_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // One write per line, so concurrent readers never see half an entry
+        file.write_all(line.as_bytes())
+    }
+
+    /// Every entry matching `query`, oldest first. A missing file is empty.
+    /// Reads the whole file, so call it from a blocking task.
+    pub fn query(&self, query: &HistoryQuery) -> io::Result<Vec<HistoryEntry>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            if let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line?) {
+                if query.matches(&entry) {
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Drop every entry older than `cutoff` and return how many went. The
+    /// file is rewritten via a temporary file and rename, like the settings.
+    /// Reads the whole file, so call it from a blocking task.
+    pub fn purge_before(&self, cutoff: DateTime<Local>) -> io::Result<usize> {
+        let _guard = WRITE_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let entries = self.query(&HistoryQuery::default())?;
+        let (keep, purged): (Vec<_>, Vec<_>) =
+            entries.into_iter().partition(|entry| entry.at >= cutoff);
+        if purged.is_empty() {
+            return Ok(0);
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        {
+            let mut file = io::BufWriter::new(fs::File::create(&tmp)?);
+            for entry in &keep {
+                serde_json::to_writer(&mut file, entry).map_err(io::Error::other)?;
+                file.write_all(b"\n")?;
+            }
+            file.into_inner()
+                .map_err(io::IntoInnerError::into_error)?
+                .sync_all()?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        Ok(purged.len())
+    }
+
+    /// Apply a retention policy of `days` (0 keeps everything).
+    pub fn purge_older_than(&self, days: u32, now: DateTime<Local>) -> io::Result<usize> {
+        if days == 0 {
+            return Ok(0);
+        }
+        self.purge_before(now - TimeDelta::days(days.into()))
+    }
+}
+
+/// Requests for the history writer thread.
+enum HistoryWrite {
+    Append(HistoryEntry),
+    /// Apply a retention policy of this many days.
+    Purge(u32),
+    /// Answer once everything sent before has been written.
+    #[cfg(test)]
+    Flush(mpsc::Sender<()>),
+}
+
+/// Turns what the scheduler loop sees into history entries.
+///
+/// Lives next to the scheduler and is fed after every poll: the countdowns
+/// reveal new ones, the held-back reminders reveal suppressions, and
+/// the due events and answers mark when a reminder was shown and closed.
+/// The entries are written by a thread of their own, so neither the disk
+/// nor a purge rewriting the file ever holds up the scheduler loop.
+pub struct HistoryRecorder {
+    writer: Option<mpsc::Sender<HistoryWrite>>,
+    /// Start of every countdown at the last check.
+    started: HashMap<ReminderType, Instant>,
+    /// When each currently held-back reminder was first seen held back.
+    held_since: HashMap<ReminderType, Instant>,
+    /// When each reminder still on screen was shown.
+    shown_at: HashMap<ReminderType, Instant>,
+    last_purge: Option<Instant>,
+}
+
+impl HistoryRecorder {
+    /// Record into `store`, if any, from a new writer thread.
+    pub fn new(store: Option<HistoryStore>) -> Self {
+        let writer = store.map(|store| {
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || write_history(&store, rx));
+            tx
+        });
+        Self {
+            writer,
+            started: HashMap::new(),
+            held_since: HashMap::new(),
+            shown_at: HashMap::new(),
+            last_purge: None,
+        }
+    }
+
+    /// Record countdowns that started since the last call, and reminders
+    /// that are no longer held back. Countdowns that merely moved, e.g.
+    /// with a snooze or a new jitter, aren't recorded again.
+    pub fn observe_schedule(&mut self, countdowns: &[Countdown], held: &[ReminderType]) {
+        let now = Instant::now();
+        for countdown in countdowns {
+            if self.started.insert(countdown.kind, countdown.started) != Some(countdown.started) {
+                self.record(
+                    countdown.kind,
+                    LifecycleEvent::Scheduled,
+                    countdown.due.saturating_duration_since(now),
+                );
+            }
+        }
+        self.started
+            .retain(|kind, _| countdowns.iter().any(|countdown| countdown.kind == *kind));
+
+        for &kind in held {
+            self.held_since.entry(kind).or_insert(now);
+        }
+        let released: Vec<(ReminderType, Instant)> = self
+            .held_since
+            .iter()
+            .filter(|(kind, _)| !held.contains(kind))
+            .map(|(&kind, &since)| (kind, since))
+            .collect();
+        for (kind, since) in released {
+            self.held_since.remove(&kind);
+            self.record(kind, LifecycleEvent::Suppressed, now - since);
+        }
+    }
+
+    /// Record a reminder the scheduler just emitted.
+    pub fn shown(&mut self, event: &ReminderEvent) {
+        let ReminderEvent::Due {
+            kind,
+            duration,
+            level,
+        } = *event;
+        self.shown_at.insert(kind, Instant::now());
+        self.record(
+            kind,
+            LifecycleEvent::Shown,
+            Duration::from_secs(duration * level.duration_factor()),
+        );
+    }
+
+    /// Record how the user answered a `kind` reminder.
+    pub fn answered(&mut self, kind: ReminderType, outcome: ReminderOutcome) {
+        let visible = self
+            .shown_at
+            .remove(&kind)
+            .map(|at| at.elapsed())
+            .unwrap_or_default();
+        self.record(kind, LifecycleEvent::answered(outcome), visible);
+    }
+
+    /// Purge entries older than `retention_days`, at most once a day.
+    pub fn enforce_retention(&mut self, retention_days: u32) {
+        if self
+            .last_purge
+            .is_some_and(|last| last.elapsed() < PURGE_INTERVAL)
+        {
+            return;
+        }
+        self.last_purge = Some(Instant::now());
+        self.send(HistoryWrite::Purge(retention_days));
+    }
+
+    /// Wait until everything recorded so far is on disk.
+    #[cfg(test)]
+    fn flush(&self) {
+        let (tx, rx) = mpsc::channel();
+        self.send(HistoryWrite::Flush(tx));
+        let _ = rx.recv();
+    }
+
+    fn record(&mut self, kind: ReminderType, event: LifecycleEvent, duration: Duration) {
+        self.send(HistoryWrite::Append(HistoryEntry {
+            at: Local::now(),
+            kind,
+            event,
+            duration: duration.as_secs(),
+        }));
+    }
+
+    fn send(&self, write: HistoryWrite) {
+        if let Some(writer) = &self.writer {
+            let _ = writer.send(write);
+        }
+    }
+}
+
+/// Carry out `writes` on `store` until the recorder goes away. Failing
+/// appends are reported once and otherwise ignored, so a read-only disk
+/// never gets in the way of the reminders themselves.
+fn write_history(store: &HistoryStore, writes: mpsc::Receiver<HistoryWrite>) {
+    let mut warned = false;
+    for write in writes {
+        match write {
+            HistoryWrite::Append(entry) => {
+                if let Err(err) = store.append(&entry) {
+                    if !warned {
+                        warned = true;
+                        eprintln!(
+                            "Warning: could not write reminder history to {}: {err}",
+                            store.path().display()
+                        );
+                    }
+                }
+            }
+            HistoryWrite::Purge(days) => {
+                if let Err(err) = store.purge_older_than(days, Local::now()) {
+                    eprintln!("Warning: could not purge {}: {err}", store.path().display());
+                }
+            }
+            #[cfg(test)]
+            HistoryWrite::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone};
+
+    fn scratch_store(name: &str) -> HistoryStore {
+        let dir = std::env::temp_dir().join(format!("blinkion-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        HistoryStore::new(dir.join(FILE_NAME))
+    }
+
+    fn scheduled(store: &HistoryStore) -> usize {
+        store
+            .query(&HistoryQuery {
+                events: vec![LifecycleEvent::Scheduled],
+                ..Default::default()
+            })
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn countdowns_are_recorded_when_they_start_but_not_when_they_move() {
+        let store = scratch_store("scheduled");
+        let mut recorder = HistoryRecorder::new(Some(store.clone()));
+        let start = Instant::now();
+        let countdown = Countdown {
+            kind: ReminderType::Blink,
+            started: start,
+            due: start + Duration::from_secs(30),
+        };
+        recorder.observe_schedule(&[countdown], &[]);
+        recorder.flush();
+        assert_eq!(scheduled(&store), 1);
+
+        // Snoozed: due later, same countdown
+        let snoozed = Countdown {
+            due: start + Duration::from_secs(300),
+            ..countdown
+        };
+        recorder.observe_schedule(&[snoozed], &[]);
+        recorder.flush();
+        assert_eq!(scheduled(&store), 1);
+
+        let restarted = Countdown {
+            started: start + Duration::from_secs(1),
+            ..countdown
+        };
+        recorder.observe_schedule(&[restarted], &[]);
+        recorder.flush();
+        assert_eq!(scheduled(&store), 2);
+    }
+
+    /// Noon on day `day` of October 2026, local time.
+    fn noon(day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, day, 12, 0, 0).unwrap()
+    }
+
+    fn entry(at: DateTime<Local>, kind: ReminderType, event: LifecycleEvent) -> HistoryEntry {
+        HistoryEntry {
+            at,
+            kind,
+            event,
+            duration: 20,
+        }
+    }
+
+    /// A store with a blink shown on the 1st, 5th and 9th of October and a
+    /// posture reminder skipped on the 5th.
+    fn filled_store(name: &str) -> HistoryStore {
+        let store = scratch_store(name);
+        for entry in [
+            entry(noon(1), ReminderType::Blink, LifecycleEvent::Shown),
+            entry(noon(5), ReminderType::Blink, LifecycleEvent::Shown),
+            entry(noon(5), ReminderType::Posture, LifecycleEvent::Skipped),
+            entry(noon(9), ReminderType::Blink, LifecycleEvent::Shown),
+        ] {
+            store.append(&entry).unwrap();
+        }
+        store
+    }
+
+    fn days(entries: Vec<HistoryEntry>) -> Vec<u32> {
+        entries.iter().map(|entry| entry.at.day()).collect()
+    }
+
+    #[test]
+    fn queries_filter_by_day_kind_and_event() {
+        let store = filled_store("query");
+        let all = store.query(&HistoryQuery::default()).unwrap();
+        assert_eq!(days(all), [1, 5, 5, 9]);
+
+        let october = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+        let middle = store.query(&HistoryQuery::days(october(2), october(5)));
+        assert_eq!(days(middle.unwrap()), [5, 5]);
+        let last = store.query(&HistoryQuery::days(october(9), october(9)));
+        assert_eq!(days(last.unwrap()), [9]);
+
+        let postures = store.query(&HistoryQuery {
+            kinds: vec![ReminderType::Posture],
+            ..Default::default()
+        });
+        assert_eq!(days(postures.unwrap()), [5]);
+        let shown = store.query(&HistoryQuery {
+            events: vec![LifecycleEvent::Shown],
+            ..Default::default()
+        });
+        assert_eq!(days(shown.unwrap()), [1, 5, 9]);
+    }
+
+    #[test]
+    fn a_missing_history_is_empty() {
+        let store = scratch_store("missing");
+        assert!(store.query(&HistoryQuery::default()).unwrap().is_empty());
+        assert_eq!(store.purge_before(noon(9)).unwrap(), 0);
+    }
+
+    #[test]
+    fn purging_keeps_everything_from_the_cutoff_on() {
+        let store = filled_store("purge");
+        assert_eq!(store.purge_before(noon(5)).unwrap(), 1);
+        let left = store.query(&HistoryQuery::default()).unwrap();
+        assert_eq!(days(left), [5, 5, 9]);
+        assert_eq!(store.purge_before(noon(5)).unwrap(), 0);
+        assert!(!store.path().with_extension("jsonl.tmp").exists());
+        // Appending carries on after the rewrite
+        store
+            .append(&entry(noon(10), ReminderType::Blink, LifecycleEvent::Shown))
+            .unwrap();
+        let all = store.query(&HistoryQuery::default()).unwrap();
+        assert_eq!(days(all), [5, 5, 9, 10]);
+    }
+
+    #[test]
+    fn retention_is_counted_in_days_back_from_now() {
+        let store = filled_store("retention");
+        assert_eq!(store.purge_older_than(0, noon(30)).unwrap(), 0);
+        assert_eq!(store.purge_older_than(7, noon(10)).unwrap(), 1);
+        let left = store.query(&HistoryQuery::default()).unwrap();
+        assert_eq!(days(left), [5, 5, 9]);
+
+        // The recorder purges through its writer thread, against the real clock
+        let store = scratch_store("recorder-retention");
+        let now = Local::now();
+        let old = entry(
+            now - TimeDelta::days(2),
+            ReminderType::Blink,
+            LifecycleEvent::Shown,
+        );
+        let recent = entry(now, ReminderType::Blink, LifecycleEvent::Shown);
+        store.append(&old).unwrap();
+        store.append(&recent).unwrap();
+        let mut recorder = HistoryRecorder::new(Some(store.clone()));
+        recorder.enforce_retention(1);
+        recorder.flush();
+        assert_eq!(store.query(&HistoryQuery::default()).unwrap(), [recent]);
+    }
+}
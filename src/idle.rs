@@ -0,0 +1,176 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often to check whether an idle user has come back.
+pub const IDLE_RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reports how long the user has gone without touching keyboard or mouse.
+pub trait IdleSource {
+    /// Time since the last user input, or `None` if it cannot be determined.
+    fn idle_time(&self) -> Option<Duration>;
+}
+
+/// Fallback for platforms without idle detection: the user is never idle.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoIdleSource;
+
+impl IdleSource for NoIdleSource {
+    fn idle_time(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Idle time from the X11 MIT-SCREEN-SAVER extension.
+#[cfg(target_os = "linux")]
+pub struct X11IdleSource {
+    conn: x11rb::rust_connection::RustConnection,
+    root: x11rb::protocol::xproto::Window,
+}
+
+#[cfg(target_os = "linux")]
+impl X11IdleSource {
+    /// Connect to `$DISPLAY`. Returns `None` if there is no X server or it
+    /// lacks the screensaver extension.
+    pub fn connect() -> Option<Self> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::screensaver::ConnectionExt;
+
+        let (conn, screen_num) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen_num)?.root;
+        conn.screensaver_query_version(1, 1).ok()?.reply().ok()?;
+        Some(Self { conn, root })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl IdleSource for X11IdleSource {
+    fn idle_time(&self) -> Option<Duration> {
+        use x11rb::protocol::screensaver::ConnectionExt;
+
+        let info = self
+            .conn
+            .screensaver_query_info(self.root)
+            .ok()?
+            .reply()
+            .ok()?;
+        Some(Duration::from_millis(info.ms_since_user_input.into()))
+    }
+}
+
+/// The best idle source available on this machine. Connecting and asking
+/// both block on the X server, so do it from a blocking task.
+pub fn system_idle_source() -> Arc<dyn IdleSource + Send + Sync> {
+    #[cfg(target_os = "linux")]
+    if let Some(source) = X11IdleSource::connect() {
+        return Arc::new(source);
+    }
+    Arc::new(NoIdleSource)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reminder::ReminderType;
+    use crate::scheduler::{Clock, FakeClock, ReminderScheduler};
+    use crate::shared_state::BlinkSettings;
+    use crate::test_support::{self, SECOND};
+    use std::time::{Duration, Instant};
+
+    fn settings() -> BlinkSettings {
+        BlinkSettings {
+            blink_interval: 600,
+            idle_threshold: 120,
+            idle_break_threshold: 1800,
+            ..test_support::settings()
+        }
+    }
+
+    fn due_times(scheduler: &ReminderScheduler<FakeClock>) -> Vec<(ReminderType, Instant)> {
+        scheduler
+            .countdowns()
+            .into_iter()
+            .map(|countdown| (countdown.kind, countdown.due))
+            .collect()
+    }
+
+    /// Let `by` pass with the user away since `since`, then report the idle time.
+    fn idle_until(
+        clock: &FakeClock,
+        scheduler: &mut ReminderScheduler<FakeClock>,
+        since: Instant,
+        by: Duration,
+    ) {
+        clock.advance(by);
+        scheduler.observe_idle(Some(clock.now() - since));
+    }
+
+    #[test]
+    fn countdowns_freeze_while_idle_and_resume_where_they_stopped() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        clock.advance(300 * SECOND);
+        let away = clock.now();
+
+        // Not idle long enough yet
+        idle_until(&clock, &mut scheduler, away, 60 * SECOND);
+        assert!(!scheduler.is_idle());
+
+        idle_until(&clock, &mut scheduler, away, 60 * SECOND);
+        assert!(scheduler.is_idle());
+        assert_eq!(scheduler.next_deadline(), None);
+        idle_until(&clock, &mut scheduler, away, 900 * SECOND);
+        assert!(scheduler.poll().is_empty());
+
+        // Back before it counts as a break: 300 s of the blink countdown remain
+        scheduler.observe_idle(Some(Duration::ZERO));
+        assert!(!scheduler.is_idle());
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 300 * SECOND));
+    }
+
+    #[test]
+    fn long_idle_counts_as_a_posture_break() {
+        let clock = FakeClock::default();
+        let start = clock.now();
+        let mut scheduler = ReminderScheduler::new(
+            clock.clone(),
+            &BlinkSettings {
+                blink_interval: 3000,
+                ..settings()
+            },
+        );
+        clock.advance(1000 * SECOND);
+        let away = clock.now();
+        idle_until(&clock, &mut scheduler, away, 1800 * SECOND);
+        assert!(scheduler.is_idle());
+
+        scheduler.observe_idle(None);
+        let back = clock.now();
+        // Blink picks up where it stopped; posture starts over
+        let due = due_times(&scheduler);
+        assert!(due.contains(&(ReminderType::Blink, start + 3000 * SECOND + (back - away))));
+        assert!(due.contains(&(ReminderType::Posture, back + 3600 * SECOND)));
+    }
+
+    #[test]
+    fn idle_breaks_count_without_freezing() {
+        let clock = FakeClock::default();
+        let start = clock.now();
+        let mut scheduler = ReminderScheduler::new(
+            clock.clone(),
+            &BlinkSettings {
+                blink_interval: 3000,
+                idle_threshold: 0,
+                ..settings()
+            },
+        );
+        clock.advance(1000 * SECOND);
+        let away = clock.now();
+        idle_until(&clock, &mut scheduler, away, 1800 * SECOND);
+        // Nothing freezes: the blink countdown keeps running
+        assert_eq!(scheduler.next_deadline(), Some(start + 3000 * SECOND));
+
+        scheduler.observe_idle(None);
+        let due = due_times(&scheduler);
+        assert!(due.contains(&(ReminderType::Blink, start + 3000 * SECOND)));
+        assert!(due.contains(&(ReminderType::Posture, clock.now() + 3600 * SECOND)));
+    }
+}
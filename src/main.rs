@@ -6,32 +6,85 @@
 //!
 //! This is useful for apps that incorporate settings panels or persistent windows like Raycast.
 
+use chrono::{DateTime, Local, NaiveTime};
 use dioxus::desktop::{trayicon::init_tray_icon, window, WindowCloseBehaviour};
 use dioxus::desktop::{Config, LogicalSize, WindowBuilder};
 use dioxus::prelude::*;
-use dioxus_desktop::muda::{Menu, MenuItem};
+use dioxus_desktop::muda::{Menu, MenuId, MenuItem, Submenu};
 use dioxus_desktop::trayicon::DioxusTrayIcon;
 use dioxus_desktop::use_muda_event_handler;
 use image::io::Reader as ImageReader;
 use image::GenericImageView;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
 
+mod adaptive;
+mod calendar;
 mod components;
+mod config;
+mod dnd;
+mod export;
+mod focus;
+mod history;
+mod idle;
+mod placement;
+mod pomodoro;
 mod reminder;
+mod schedule;
+mod scheduler;
 mod shared_state;
-mod signals;
+mod sink;
+mod sound;
+mod stats;
+#[cfg(test)]
+mod test_support;
 
+use crate::adaptive::AdaptiveIntervals;
+use crate::calendar::{CalendarFiles, CALENDAR_RECHECK_INTERVAL};
+use crate::export::{ExportRequest, USAGE};
+use crate::focus::FOCUS_RECHECK_INTERVAL;
+use crate::history::{HistoryRecorder, HistoryStore};
+use crate::idle::IDLE_RECHECK_INTERVAL;
 use crate::shared_state::{
-    get_blink_interval, get_posture_duration, get_posture_interval, settings_receiver,
+    apply_adaptive_adjustment, command_receiver, get_calendar_files, get_history_retention_days,
+    get_settings, pomodoro_status_receiver, reminder_receiver, send_command, set_pomodoro_status,
+    settings_receiver, REMINDER_CHANNEL,
 };
-use components::reminder_window::{reminder_window, ReminderWindowProps};
 use components::settings_window::SettingsWindow;
-use reminder::ReminderType;
-use signals::SHOW_WINDOW;
+use components::statistics_window::StatisticsWindow;
+use pomodoro::PomodoroStatus;
+use scheduler::{ReminderEvent, ReminderScheduler, SchedulerCommand, SystemClock};
+use sink::ReminderSinks;
 
 pub const STYLE: Asset = asset!("/assets/tailwind.css");
 
 fn main() {
+    // `--export` writes the reminder history and exits without starting the app
+    match ExportRequest::from_args(std::env::args().skip(1), Local::now().date_naive()) {
+        // Read the settings without the app's loader, which backs up a malformed file
+        Ok(Some(request)) => match request.run(&config::load_read_only()) {
+            Ok(count) => {
+                if let Some(path) = &request.output {
+                    eprintln!("Exported {count} records to {}", path.display());
+                }
+                return;
+            }
+            Err(err) => {
+                eprintln!("Error: {err}");
+                std::process::exit(1);
+            }
+        },
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("Error: {err}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    }
+
+    // Load persisted settings up front so a bad config file is reported at launch
+    once_cell::sync::Lazy::force(&shared_state::SHARED_BLINK_SETTINGS);
+
     dioxus::LaunchBuilder::new()
         .with_cfg(
             Config::default().with_window(
@@ -47,15 +100,86 @@ fn main() {
         .launch(app);
 }
 
+/// Pause lengths offered in the tray menu.
+#[derive(Clone, Copy)]
+enum PauseChoice {
+    For(Duration),
+    UntilMorning,
+    Indefinitely,
+}
+
+impl PauseChoice {
+    const ALL: [PauseChoice; 4] = [
+        PauseChoice::For(Duration::from_secs(15 * 60)),
+        PauseChoice::For(Duration::from_secs(60 * 60)),
+        PauseChoice::UntilMorning,
+        PauseChoice::Indefinitely,
+    ];
+
+    fn label(self) -> String {
+        match self {
+            PauseChoice::For(length) if length.as_secs() < 3600 => {
+                format!("{} minutes", length.as_secs() / 60)
+            }
+            PauseChoice::For(length) if length.as_secs() == 3600 => "1 hour".to_string(),
+            PauseChoice::For(length) => format!("{} hours", length.as_secs() / 3600),
+            PauseChoice::UntilMorning => "Until tomorrow morning".to_string(),
+            PauseChoice::Indefinitely => "Indefinitely".to_string(),
+        }
+    }
+
+    /// How long to pause for, measured from now. `None` pauses indefinitely.
+    fn length(self) -> Option<Duration> {
+        match self {
+            PauseChoice::For(length) => Some(length),
+            PauseChoice::UntilMorning => Some(until_next_morning(Local::now())),
+            PauseChoice::Indefinitely => None,
+        }
+    }
+}
+
+/// Time from `now` until the next 8:00 local time.
+fn until_next_morning(now: DateTime<Local>) -> Duration {
+    let morning = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+    let mut day = now.date_naive();
+    if now.time() >= morning {
+        day = day.succ_opt().unwrap_or(day);
+    }
+    day.and_time(morning)
+        .and_local_timezone(Local)
+        .earliest()
+        .and_then(|target| (target - now).to_std().ok())
+        .unwrap_or(Duration::from_secs(8 * 3600))
+}
+
 fn app() -> Element {
     // Listen for tray menu events
 
     window().set_close_behavior(WindowCloseBehaviour::WindowHides);
-    // Create a tray menu with a Settings item
+    // Create a tray menu with Settings, Statistics, Pause and Resume items
     let tray_menu = Menu::new();
     let menu_item = MenuItem::new("Settings", true, None);
     let menu_item_id = menu_item.id().clone();
     let _ = tray_menu.append(&menu_item);
+    let stats_item = MenuItem::new("Statistics", true, None);
+    let stats_item_id = stats_item.id().clone();
+    let _ = tray_menu.append(&stats_item);
+
+    let pause_menu = Submenu::new("Pause", true);
+    let pause_items: Vec<(MenuId, PauseChoice)> = PauseChoice::ALL
+        .into_iter()
+        .map(|choice| {
+            let item = MenuItem::new(choice.label(), true, None);
+            let _ = pause_menu.append(&item);
+            (item.id().clone(), choice)
+        })
+        .collect();
+    let _ = tray_menu.append(&pause_menu);
+    let resume_item = MenuItem::new("Resume", true, None);
+    let resume_item_id = resume_item.id().clone();
+    let _ = tray_menu.append(&resume_item);
+    let pomodoro_item = MenuItem::new(pomodoro_label(None), false, None);
+    let _ = tray_menu.append(&pomodoro_item);
 
     // Decode PNG at runtime for tray icon
     let img = ImageReader::open("assets/tray.png")
@@ -80,104 +204,178 @@ fn app() -> Element {
                         .with_inner_size(LogicalSize::new(800.0, 500.0)),
                 ),
             );
+        } else if *event.id() == stats_item_id {
+            window().new_window(
+                VirtualDom::new(StatisticsWindow),
+                Config::default().with_window(
+                    WindowBuilder::new()
+                        .with_title("Blinkion Statistics")
+                        .with_transparent(false)
+                        .with_decorations(true)
+                        .with_inner_size(LogicalSize::new(900.0, 700.0)),
+                ),
+            );
+        } else if *event.id() == resume_item_id {
+            send_command(SchedulerCommand::Resume);
+        } else if let Some((_, choice)) = pause_items.iter().find(|(id, _)| id == event.id()) {
+            send_command(SchedulerCommand::Pause(choice.length()));
         }
     });
 
-    // Timer: set SHOW_WINDOW to true every blink_interval seconds
-    // Listen for blink interval changes via the broadcast channel
-    use_effect(move || {
-        spawn(async move {
-            use std::time::{Duration, Instant};
-            let mut last_trigger = Instant::now();
-            let mut interval = get_blink_interval();
+    // Drive the reminder scheduler and publish due reminders on REMINDER_CHANNEL.
+    // The task sleeps until the scheduler's next deadline or until a settings
+    // change, command or Do Not Disturb switch arrives, so it does no work
+    // between reminders.
+    use_future(|| async move {
+        let mut scheduler = ReminderScheduler::new(SystemClock, &get_settings());
+        let (idle_source, focus_guard) = tokio::task::spawn_blocking(|| {
+            (idle::system_idle_source(), focus::system_focus_guard())
+        })
+        .await
+        .expect("platform probes panicked");
+        let mut dnd = dnd::system_dnd_source().await.watch();
+        let mut calendar_files = CalendarFiles::default();
+        let mut adaptive = AdaptiveIntervals::default();
+        let mut history = HistoryRecorder::new(HistoryStore::open_default());
 
-            // Create a new broadcast receiver for settings changes
-            let mut rx = settings_receiver();
+        // Create new broadcast receivers for settings changes and commands
+        let mut rx = settings_receiver();
+        let mut commands = command_receiver();
 
-            loop {
-                tokio::time::sleep(Duration::from_millis(100)).await;
-                // Check for new settings
-                while let Ok(settings) = rx.try_recv() {
-                    interval = settings.blink_interval;
-                }
-                if last_trigger.elapsed().as_secs() >= interval {
-                    *SHOW_WINDOW.write() = true;
-                    last_trigger = Instant::now();
+        loop {
+            // While the user is idle, keep checking for their return
+            let idle_recheck = scheduler
+                .is_idle()
+                .then(|| Instant::now() + IDLE_RECHECK_INTERVAL);
+            // ...and for a fullscreen app to go away
+            let focus_recheck = scheduler
+                .is_fullscreen()
+                .then(|| Instant::now() + FOCUS_RECHECK_INTERVAL);
+            // ...and for edits to the calendar files
+            let calendar_recheck = (!get_calendar_files().is_empty())
+                .then(|| Instant::now() + CALENDAR_RECHECK_INTERVAL);
+            let wake_at = scheduler
+                .next_deadline()
+                .into_iter()
+                .chain(idle_recheck)
+                .chain(focus_recheck)
+                .chain(calendar_recheck)
+                .min();
+
+            tokio::select! {
+                _ = sleep_until(wake_at) => {}
+                Ok(settings) = rx.recv() => scheduler.apply_settings(&settings),
+                Ok(()) = dnd.changed() => {}
+                Ok(command) = commands.recv() => {
+                    scheduler.handle(command);
+                    if let SchedulerCommand::Respond { kind, outcome } = command {
+                        history.answered(kind, outcome);
+                        // Lengthen intervals the user keeps brushing off
+                        if let Some(adjustment) = adaptive.record(kind, outcome, &get_settings()) {
+                            apply_adaptive_adjustment(adjustment);
+                        }
+                    }
                 }
             }
-        });
-    });
-
-    // Posture Reminder Timer: triggers posture reminder window every posture_interval seconds
-    use_effect(move || {
-        spawn(async move {
-            use std::time::{Duration, Instant};
-            let mut last_trigger = Instant::now();
-            let mut interval = get_posture_interval();
-            let mut duration = get_posture_duration();
 
-            // Create a new broadcast receiver for settings changes
-            let mut rx = settings_receiver();
+            // Freeze the countdowns while nobody is at the machine
+            scheduler.observe_idle(probe(&idle_source, |source| source.idle_time()).await);
+            // Hold popups back while a presentation or video is fullscreen
+            scheduler.observe_fullscreen(probe(&focus_guard, |guard| guard.is_fullscreen()).await);
+            // Queue popups while the desktop is in Do Not Disturb mode
+            scheduler.observe_dnd(*dnd.borrow_and_update());
+            if let Some(calendar) = calendar_files.refresh(&get_calendar_files()) {
+                scheduler.set_calendar(calendar);
+            }
+            for event in scheduler.poll() {
+                history.shown(&event);
+                let _ = REMINDER_CHANNEL.send(event);
+            }
+            history.observe_schedule(&scheduler.countdowns(), &scheduler.held_back());
+            history.enforce_retention(get_history_retention_days());
+            set_pomodoro_status(scheduler.pomodoro_status());
+        }
+    });
 
+    // Keep the Pomodoro phase and time left in the tray menu current. While
+    // a session runs the label wakes once a minute, when the minutes left go
+    // down; otherwise it waits for the next phase change.
+    use_future(move || {
+        let pomodoro_item = pomodoro_item.clone();
+        async move {
+            let mut rx = pomodoro_status_receiver();
             loop {
-                tokio::time::sleep(Duration::from_millis(100)).await;
-                // Check for new settings
-                while let Ok(settings) = rx.try_recv() {
-                    interval = settings.posture_interval;
-                    duration = settings.posture_duration;
-                }
-                if last_trigger.elapsed().as_secs() >= interval {
-                    // Open a posture reminder window, passing duration as a prop
-                    window().new_window(
-                        VirtualDom::new_with_props(
-                            reminder_window,
-                            ReminderWindowProps {
-                                kind: ReminderType::Posture,
-                                duration: Some(duration),
-                            },
-                        ),
-                        Config::default().with_window(
-                            WindowBuilder::new()
-                                .with_title("Posture Reminder")
-                                .with_transparent(true)
-                                .with_always_on_top(true)
-                                .with_decorations(false)
-                                .with_inner_size(LogicalSize::new(240.0, 200.0)),
-                        ),
-                    );
-                    // Wait for the posture duration before closing (handled in ReminderWindow)
-                    last_trigger = Instant::now();
+                let status = *rx.borrow_and_update();
+                pomodoro_item.set_text(pomodoro_label(status));
+                let refresh_at = status.and_then(|status| status.next_minute(Instant::now()));
+                let changed = tokio::select! {
+                    changed = rx.changed() => changed,
+                    _ = sleep_until(refresh_at) => Ok(()),
+                };
+                if changed.is_err() {
+                    break;
                 }
             }
-        });
+        }
     });
 
-    // Poll SHOW_WINDOW every 100ms, show window if true, then reset
+    // Deliver every reminder the scheduler emits through the sink chosen for its type
     use_future(|| async move {
+        let sinks = ReminderSinks::system().await;
+        let mut rx = reminder_receiver();
         loop {
-            tokio::time::sleep(Duration::from_millis(100)).await;
-            if *SHOW_WINDOW.read() {
-                window().new_window(
-                    VirtualDom::new_with_props(
-                        reminder_window,
-                        ReminderWindowProps {
-                            kind: ReminderType::Blink,
-                            duration: None,
-                        },
-                    ),
-                    Config::default().with_window(
-                        WindowBuilder::new()
-                            .with_title("Blinkion")
-                            .with_transparent(true)
-                            .with_always_on_top(true)
-                            .with_decorations(false)
-                            .with_inner_size(LogicalSize::new(300.0, 300.0)),
-                    ),
-                );
-                *SHOW_WINDOW.write() = false;
+            match rx.recv().await {
+                Ok(ReminderEvent::Due {
+                    kind,
+                    duration,
+                    level,
+                }) => {
+                    let settings = get_settings();
+                    let options = settings.delivery_for(kind);
+                    if !settings.sound_muted {
+                        sound::play(&options.sound, options.volume as f32);
+                    }
+                    sinks.deliver(&settings, kind, duration, level);
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
             }
         }
     });
 
     VNode::empty()
 }
+
+/// Tray text for the Pomodoro timer, e.g. "Pomodoro: Work, 12 min left".
+fn pomodoro_label(status: Option<PomodoroStatus>) -> String {
+    match status {
+        Some(status) => format!(
+            "Pomodoro: {}, {} min left",
+            status.phase,
+            status.minutes_left(Instant::now())
+        ),
+        None => "Pomodoro: off".to_string(),
+    }
+}
+
+/// Ask a platform `source` something on the blocking thread pool, so that a
+/// slow X server never stalls the UI. A probe that panics answers with the
+/// default.
+async fn probe<S, T>(source: &Arc<S>, ask: impl FnOnce(&S) -> T + Send + 'static) -> T
+where
+    S: ?Sized + Send + Sync + 'static,
+    T: Default + Send + 'static,
+{
+    let source = Arc::clone(source);
+    tokio::task::spawn_blocking(move || ask(&source))
+        .await
+        .unwrap_or_default()
+}
+
+/// Sleep until `deadline`, or forever if there is none.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => std::future::pending().await,
+    }
+}
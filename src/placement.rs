@@ -0,0 +1,274 @@
+use crate::reminder::{DeliverySettings, ReminderType};
+use serde::{Deserialize, Serialize};
+
+/// Which monitor a reminder window appears on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorChoice {
+    /// The monitor the mouse cursor is on.
+    #[default]
+    Cursor,
+    /// The primary monitor.
+    Primary,
+    /// Every monitor at once, one window each.
+    All,
+}
+
+impl MonitorChoice {
+    pub const ALL: [MonitorChoice; 3] = [
+        MonitorChoice::Cursor,
+        MonitorChoice::Primary,
+        MonitorChoice::All,
+    ];
+
+    /// Name shown in the settings window.
+    pub fn label(self) -> &'static str {
+        match self {
+            MonitorChoice::Cursor => "Under cursor",
+            MonitorChoice::Primary => "Primary",
+            MonitorChoice::All => "All monitors",
+        }
+    }
+}
+
+/// Where on its monitor a reminder window appears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    #[default]
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// Just below and to the right of the mouse cursor.
+    NearCursor,
+}
+
+impl Anchor {
+    pub const ALL: [Anchor; 6] = [
+        Anchor::Center,
+        Anchor::TopLeft,
+        Anchor::TopRight,
+        Anchor::BottomLeft,
+        Anchor::BottomRight,
+        Anchor::NearCursor,
+    ];
+
+    /// Name shown in the settings window.
+    pub fn label(self) -> &'static str {
+        match self {
+            Anchor::Center => "Center",
+            Anchor::TopLeft => "Top left",
+            Anchor::TopRight => "Top right",
+            Anchor::BottomLeft => "Bottom left",
+            Anchor::BottomRight => "Bottom right",
+            Anchor::NearCursor => "Near cursor",
+        }
+    }
+}
+
+/// Size of a `kind` reminder window at scale 1, in logical pixels.
+pub fn base_size(kind: ReminderType) -> (f64, f64) {
+    match kind {
+        ReminderType::Blink => (300.0, 340.0),
+        ReminderType::Posture => (260.0, 250.0),
+        ReminderType::EyeBreak
+        | ReminderType::MeetingBreak
+        | ReminderType::PomodoroBreak { .. } => (320.0, 300.0),
+        ReminderType::Custom(_) => (300.0, 280.0),
+    }
+}
+
+/// A screen area in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    fn contains(&self, (x, y): (f64, f64)) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Top-left corner that centers a `width` x `height` window in `area`.
+fn centered(area: Rect, width: f64, height: f64) -> (f64, f64) {
+    (
+        area.x + (area.width - width) / 2.0,
+        area.y + (area.height - height) / 2.0,
+    )
+}
+
+/// Top-left corner for a `width` x `height` window on the monitor covering
+/// `area`, all in physical pixels. `scale_factor` converts the logical margin
+/// and remembered position in `options`.
+///
+/// A remembered drag position wins over the anchor. The window is kept inside
+/// `area` either way.
+pub fn window_position(
+    area: Rect,
+    width: f64,
+    height: f64,
+    scale_factor: f64,
+    options: &DeliverySettings,
+    cursor: Option<(f64, f64)>,
+) -> (f64, f64) {
+    let margin = f64::from(options.margin) * scale_factor;
+    let (x, y) = match (options.dragged_position, options.anchor) {
+        (Some((x, y)), _) => (area.x + x * scale_factor, area.y + y * scale_factor),
+        (None, Anchor::Center) => centered(area, width, height),
+        (None, Anchor::TopLeft) => (area.x + margin, area.y + margin),
+        (None, Anchor::TopRight) => (area.x + area.width - width - margin, area.y + margin),
+        (None, Anchor::BottomLeft) => (area.x + margin, area.y + area.height - height - margin),
+        (None, Anchor::BottomRight) => (
+            area.x + area.width - width - margin,
+            area.y + area.height - height - margin,
+        ),
+        // The cursor may be on another monitor when showing on all of them
+        (None, Anchor::NearCursor) => match cursor.filter(|&cursor| area.contains(cursor)) {
+            Some((x, y)) => (x + margin, y + margin),
+            None => centered(area, width, height),
+        },
+    };
+    (
+        x.min(area.x + area.width - width).max(area.x),
+        y.min(area.y + area.height - height).max(area.y),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: Rect = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 1920.0,
+        height: 1080.0,
+    };
+
+    fn anchored(anchor: Anchor) -> DeliverySettings {
+        DeliverySettings {
+            anchor,
+            ..Default::default()
+        }
+    }
+
+    fn dragged_to(x: f64, y: f64) -> DeliverySettings {
+        DeliverySettings {
+            dragged_position: Some((x, y)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn anchors_place_the_window_a_margin_from_their_edges() {
+        let at = |anchor| window_position(PRIMARY, 300.0, 200.0, 1.0, &anchored(anchor), None);
+        assert_eq!(at(Anchor::Center), (810.0, 440.0));
+        assert_eq!(at(Anchor::TopLeft), (24.0, 24.0));
+        assert_eq!(at(Anchor::TopRight), (1596.0, 24.0));
+        assert_eq!(at(Anchor::BottomLeft), (24.0, 856.0));
+        assert_eq!(at(Anchor::BottomRight), (1596.0, 856.0));
+        // Without a cursor on this monitor there's nothing to be near
+        assert_eq!(at(Anchor::NearCursor), (810.0, 440.0));
+        let options = anchored(Anchor::NearCursor);
+        assert_eq!(
+            window_position(PRIMARY, 300.0, 200.0, 1.0, &options, Some((100.0, 100.0))),
+            (124.0, 124.0)
+        );
+    }
+
+    #[test]
+    fn margins_and_drag_positions_are_scaled_to_physical_pixels() {
+        let right = Rect {
+            x: 1920.0,
+            ..PRIMARY
+        };
+        let options = anchored(Anchor::TopLeft);
+        assert_eq!(
+            window_position(right, 600.0, 400.0, 2.0, &options, None),
+            (1968.0, 48.0)
+        );
+        assert_eq!(
+            window_position(right, 600.0, 400.0, 2.0, &dragged_to(100.0, 50.0), None),
+            (2120.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn windows_are_kept_on_their_monitor() {
+        let options = anchored(Anchor::NearCursor);
+        assert_eq!(
+            window_position(PRIMARY, 300.0, 200.0, 1.0, &options, Some((1900.0, 1070.0))),
+            (1620.0, 880.0)
+        );
+        let options = DeliverySettings {
+            margin: 2000,
+            ..anchored(Anchor::TopLeft)
+        };
+        assert_eq!(
+            window_position(PRIMARY, 300.0, 200.0, 1.0, &options, None),
+            (1620.0, 880.0)
+        );
+        // Too big to fit, so at least the top-left corner shows
+        let options = anchored(Anchor::BottomRight);
+        assert_eq!(
+            window_position(PRIMARY, 3000.0, 2000.0, 1.0, &options, None),
+            (0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn a_drag_position_now_off_screen_is_pulled_back() {
+        // e.g. remembered from a larger monitor than the one showing it now
+        assert_eq!(
+            window_position(
+                PRIMARY,
+                300.0,
+                200.0,
+                1.0,
+                &dragged_to(5000.0, -300.0),
+                None
+            ),
+            (1620.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn a_monitor_left_of_and_above_the_primary_is_placed_on() {
+        let secondary = Rect {
+            x: -2560.0,
+            y: -360.0,
+            width: 2560.0,
+            height: 1440.0,
+        };
+        let cursor = Some((-100.0, -300.0));
+        let center = anchored(Anchor::Center);
+        assert_eq!(
+            window_position(secondary, 300.0, 200.0, 1.0, &center, cursor),
+            (-1430.0, 260.0)
+        );
+        let near = anchored(Anchor::NearCursor);
+        assert_eq!(
+            window_position(secondary, 300.0, 200.0, 1.0, &near, cursor),
+            (-300.0, -276.0)
+        );
+        // The windows shown on every monitor only follow the cursor on its own
+        assert_eq!(
+            window_position(PRIMARY, 300.0, 200.0, 1.0, &near, cursor),
+            (810.0, 440.0)
+        );
+        assert_eq!(
+            window_position(PRIMARY, 300.0, 200.0, 1.0, &near, Some((-1.0, 0.0))),
+            (810.0, 440.0)
+        );
+        assert_eq!(
+            window_position(PRIMARY, 300.0, 200.0, 1.0, &near, Some((0.0, 0.0))),
+            (24.0, 24.0)
+        );
+    }
+}
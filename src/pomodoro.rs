@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// Lengths of the Pomodoro work sessions and breaks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PomodoroSettings {
+    pub enabled: bool,
+    pub work_minutes: u64,
+    pub short_break_minutes: u64,
+    pub long_break_minutes: u64,
+    /// Work sessions per cycle; the break after the last one is a long break.
+    pub cycles_before_long_break: u32,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            cycles_before_long_break: 4,
+        }
+    }
+}
+
+impl PomodoroSettings {
+    fn length(&self, phase: PomodoroPhase) -> Duration {
+        let minutes = match phase {
+            PomodoroPhase::Work => self.work_minutes,
+            PomodoroPhase::ShortBreak => self.short_break_minutes,
+            PomodoroPhase::LongBreak => self.long_break_minutes,
+        };
+        Duration::from_secs(minutes.max(1) * 60)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PomodoroPhase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl PomodoroPhase {
+    pub fn is_break(self) -> bool {
+        self != PomodoroPhase::Work
+    }
+}
+
+impl fmt::Display for PomodoroPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PomodoroPhase::Work => "Work",
+            PomodoroPhase::ShortBreak => "Short break",
+            PomodoroPhase::LongBreak => "Long break",
+        })
+    }
+}
+
+/// A snapshot of the Pomodoro timer for display in the tray.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PomodoroStatus {
+    pub phase: PomodoroPhase,
+    pub ends_at: Instant,
+}
+
+impl PomodoroStatus {
+    /// Whole minutes left in the phase at `now`, rounded up.
+    pub fn minutes_left(&self, now: Instant) -> u64 {
+        let left = self.ends_at.saturating_duration_since(now);
+        left.as_nanos().div_ceil(MINUTE.as_nanos()) as u64
+    }
+
+    /// When [`minutes_left`](Self::minutes_left) next goes down, or `None`
+    /// once the phase is over.
+    pub fn next_minute(&self, now: Instant) -> Option<Instant> {
+        let minutes = self.minutes_left(now).checked_sub(1)?;
+        Some(self.ends_at - MINUTE * minutes as u32)
+    }
+}
+
+/// Pomodoro state machine: work, break, work, ... with a long break every
+/// `cycles_before_long_break` work sessions.
+#[derive(Debug, Clone)]
+pub struct Pomodoro {
+    settings: PomodoroSettings,
+    phase: PomodoroPhase,
+    phase_end: Instant,
+    /// Work sessions finished in the current cycle.
+    completed: u32,
+}
+
+impl Pomodoro {
+    /// Start a new cycle with a work session beginning at `now`.
+    pub fn new(settings: PomodoroSettings, now: Instant) -> Self {
+        let phase_end = now + settings.length(PomodoroPhase::Work);
+        Self {
+            settings,
+            phase: PomodoroPhase::Work,
+            phase_end,
+            completed: 0,
+        }
+    }
+
+    /// Use new lengths from the next phase on.
+    pub fn apply_settings(&mut self, settings: PomodoroSettings) {
+        self.settings = settings;
+    }
+
+    pub fn status(&self) -> PomodoroStatus {
+        PomodoroStatus {
+            phase: self.phase,
+            ends_at: self.phase_end,
+        }
+    }
+
+    pub fn phase(&self) -> PomodoroPhase {
+        self.phase
+    }
+
+    pub fn phase_end(&self) -> Instant {
+        self.phase_end
+    }
+
+    /// Length of the current phase.
+    pub fn phase_length(&self) -> Duration {
+        self.settings.length(self.phase)
+    }
+
+    /// Move to the next phase if the current one has ended, returning the
+    /// phase that just started.
+    pub fn advance(&mut self, now: Instant) -> Option<PomodoroPhase> {
+        if now < self.phase_end {
+            return None;
+        }
+        let next = match self.phase {
+            PomodoroPhase::Work => {
+                self.completed += 1;
+                if self.completed >= self.settings.cycles_before_long_break.max(1) {
+                    self.completed = 0;
+                    PomodoroPhase::LongBreak
+                } else {
+                    PomodoroPhase::ShortBreak
+                }
+            }
+            PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => PomodoroPhase::Work,
+        };
+        self.start(next, now);
+        Some(next)
+    }
+
+    /// Start a fresh work session at `now`, keeping the cycle count.
+    pub fn restart_work(&mut self, now: Instant) {
+        self.start(PomodoroPhase::Work, now);
+    }
+
+    /// Put off the current break: go back to work for `by`, after which the
+    /// same kind of break starts again.
+    pub fn postpone_break(&mut self, now: Instant, by: Duration) {
+        if !self.phase.is_break() {
+            return;
+        }
+        self.completed = match self.phase {
+            PomodoroPhase::LongBreak => self.settings.cycles_before_long_break.max(1) - 1,
+            _ => self.completed.saturating_sub(1),
+        };
+        self.phase = PomodoroPhase::Work;
+        self.phase_end = now + by;
+    }
+
+    fn start(&mut self, phase: PomodoroPhase, now: Instant) {
+        self.phase = phase;
+        self.phase_end = now + self.settings.length(phase);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minutes_left_count_down_on_the_minute() {
+        let start = Instant::now();
+        let status = PomodoroStatus {
+            phase: PomodoroPhase::Work,
+            ends_at: start + MINUTE * 25,
+        };
+        assert_eq!(status.minutes_left(start), 25);
+        assert_eq!(status.next_minute(start), Some(start + MINUTE));
+
+        let later = start + Duration::from_secs(90);
+        assert_eq!(status.minutes_left(later), 24);
+        assert_eq!(status.next_minute(later), Some(start + MINUTE * 2));
+
+        let last = status.ends_at - Duration::from_millis(1);
+        assert_eq!(status.minutes_left(last), 1);
+        assert_eq!(status.next_minute(last), Some(status.ends_at));
+        assert_eq!(status.minutes_left(status.ends_at), 0);
+        assert_eq!(status.next_minute(status.ends_at + MINUTE), None);
+    }
+}
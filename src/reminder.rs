@@ -1,5 +1,214 @@
-#[derive(Clone, Copy, PartialEq)]
+use crate::placement::{Anchor, MonitorChoice};
+use crate::sink::SinkKind;
+use crate::sound::SoundCue;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long "Snooze" postpones a reminder.
+pub const SNOOZE_LENGTH: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ReminderType {
     Blink,
     Posture,
+    /// 20-20-20 rule: look 20 feet away for 20 seconds every 20 minutes.
+    EyeBreak,
+    /// A user-defined reminder, identified by [`CustomReminder::id`].
+    Custom(u32),
+    /// A break right after a calendar meeting ends.
+    MeetingBreak,
+    /// A short or long break between Pomodoro work sessions.
+    PomodoroBreak {
+        long: bool,
+    },
+}
+
+impl ReminderType {
+    /// The reminder types that always exist, whether or not they are enabled.
+    pub const BUILT_IN: [ReminderType; 3] = [
+        ReminderType::Blink,
+        ReminderType::Posture,
+        ReminderType::EyeBreak,
+    ];
+
+    /// Stable name used to store per-type settings. Short and long Pomodoro
+    /// breaks share one key.
+    pub fn key(self) -> String {
+        match self {
+            ReminderType::Blink => "blink".to_string(),
+            ReminderType::Posture => "posture".to_string(),
+            ReminderType::EyeBreak => "eye_break".to_string(),
+            ReminderType::Custom(id) => format!("custom_{id}"),
+            ReminderType::MeetingBreak => "meeting_break".to_string(),
+            ReminderType::PomodoroBreak { .. } => "pomodoro_break".to_string(),
+        }
+    }
+
+    /// Whether the reminder is a quick prompt that is meant to go away on
+    /// its own, so that it closing without an answer isn't ignoring it.
+    pub fn closes_itself(self) -> bool {
+        matches!(self, ReminderType::Blink | ReminderType::Posture)
+    }
+}
+
+/// How reminders of one type reach the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeliverySettings {
+    /// Hold popups back while the desktop is in Do Not Disturb mode.
+    pub respect_dnd: bool,
+    pub sink: SinkKind,
+    /// Which monitor the popup appears on.
+    pub monitor: MonitorChoice,
+    pub anchor: Anchor,
+    /// Distance from the anchored screen edges (or the cursor), in logical pixels.
+    pub margin: u32,
+    /// Size relative to the default window size.
+    pub scale: f64,
+    /// From 0 (invisible) to 1 (opaque).
+    pub opacity: f64,
+    /// Where the user last dragged the popup to, in logical pixels from the
+    /// top-left corner of its monitor. Takes precedence over `anchor`.
+    pub dragged_position: Option<(f64, f64)>,
+    /// Played when the reminder appears.
+    pub sound: SoundCue,
+    /// From 0 (silent) to 1 (full volume).
+    pub volume: f64,
+}
+
+impl Default for DeliverySettings {
+    fn default() -> Self {
+        Self {
+            respect_dnd: true,
+            sink: SinkKind::default(),
+            monitor: MonitorChoice::default(),
+            anchor: Anchor::default(),
+            margin: 24,
+            scale: 1.0,
+            opacity: 1.0,
+            dragged_position: None,
+            sound: SoundCue::default(),
+            volume: 0.8,
+        }
+    }
+}
+
+/// How the user responded to a reminder window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReminderOutcome {
+    /// The break was taken.
+    Done,
+    /// Show the reminder again after [`SNOOZE_LENGTH`].
+    Snoozed,
+    /// Dismissed without taking the break.
+    Skipped,
+    /// Closed on its own without an answer.
+    Ignored,
+}
+
+/// How prominently a reminder is shown. Rises while reminders of one type
+/// keep being ignored or skipped, and drops back once one is acknowledged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EscalationLevel {
+    /// The normal small popup.
+    #[default]
+    Subtle,
+    /// A larger popup that stays open longer.
+    Prominent,
+    /// A full-screen overlay.
+    Fullscreen,
+}
+
+impl EscalationLevel {
+    /// The level after `ignored` consecutive unanswered reminders. A
+    /// threshold of 0 never reaches that level.
+    pub fn after(ignored: u32, prominent_after: u32, fullscreen_after: u32) -> Self {
+        let reached = |threshold: u32| threshold > 0 && ignored >= threshold;
+        if reached(fullscreen_after) {
+            EscalationLevel::Fullscreen
+        } else if reached(prominent_after) {
+            EscalationLevel::Prominent
+        } else {
+            EscalationLevel::Subtle
+        }
+    }
+
+    /// How many times the configured duration the window stays open.
+    pub fn duration_factor(self) -> u64 {
+        match self {
+            EscalationLevel::Subtle => 1,
+            EscalationLevel::Prominent => 2,
+            EscalationLevel::Fullscreen => 3,
+        }
+    }
+
+    /// How much to enlarge the reminder content.
+    pub fn scale(self) -> f64 {
+        match self {
+            EscalationLevel::Subtle => 1.0,
+            EscalationLevel::Prominent => 1.5,
+            EscalationLevel::Fullscreen => 2.0,
+        }
+    }
+}
+
+/// When a reminder with a countdown fires and how long it stays up, the
+/// same for built-in and user-defined reminders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReminderDefinition {
+    pub kind: ReminderType,
+    pub enabled: bool,
+    /// Seconds between reminders.
+    pub interval: u64,
+    /// Seconds the reminder window stays open.
+    pub duration: u64,
+    /// Random deviation from `interval`, in percent (0 disables).
+    pub jitter: u8,
+}
+
+/// A user-defined reminder such as "drink water" or "stretch your wrists".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomReminder {
+    /// Stable identifier, unique among custom reminders.
+    pub id: u32,
+    pub enabled: bool,
+    pub name: String,
+    /// Seconds between reminders.
+    pub interval: u64,
+    /// Seconds the reminder window stays open.
+    pub duration: u64,
+    /// Random deviation from `interval`, in percent (0 disables).
+    pub jitter: u8,
+    pub message: String,
+    /// An emoji or short text shown above the message.
+    pub icon: String,
+}
+
+impl Default for CustomReminder {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            enabled: true,
+            name: "Hydrate".to_string(),
+            interval: 45 * 60,
+            duration: 10,
+            jitter: 0,
+            message: "Time for a glass of water.".to_string(),
+            icon: "💧".to_string(),
+        }
+    }
+}
+
+impl CustomReminder {
+    pub fn definition(&self) -> ReminderDefinition {
+        ReminderDefinition {
+            kind: ReminderType::Custom(self.id),
+            enabled: self.enabled,
+            interval: self.interval,
+            duration: self.duration,
+            jitter: self.jitter,
+        }
+    }
 }
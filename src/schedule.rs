@@ -0,0 +1,226 @@
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// A span of the day during which reminders may fire.
+///
+/// A range whose `end` is not after its `start` runs past midnight into the
+/// next day, e.g. 22:00-02:00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeRange {
+    pub fn new(start: NaiveTime, end: NaiveTime) -> Self {
+        Self { start, end }
+    }
+
+    fn wraps_midnight(&self) -> bool {
+        self.end <= self.start
+    }
+}
+
+/// Per-weekday active time ranges. Reminders only fire inside a range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeeklySchedule {
+    /// When disabled, reminders fire at any time.
+    pub enabled: bool,
+    pub monday: Vec<TimeRange>,
+    pub tuesday: Vec<TimeRange>,
+    pub wednesday: Vec<TimeRange>,
+    pub thursday: Vec<TimeRange>,
+    pub friday: Vec<TimeRange>,
+    pub saturday: Vec<TimeRange>,
+    pub sunday: Vec<TimeRange>,
+}
+
+impl Default for WeeklySchedule {
+    /// Disabled, but pre-filled with 09:00-17:00 on weekdays so switching it
+    /// on gives a sensible schedule.
+    fn default() -> Self {
+        let office = vec![TimeRange::new(
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        )];
+        Self {
+            enabled: false,
+            monday: office.clone(),
+            tuesday: office.clone(),
+            wednesday: office.clone(),
+            thursday: office.clone(),
+            friday: office,
+            saturday: Vec::new(),
+            sunday: Vec::new(),
+        }
+    }
+}
+
+impl WeeklySchedule {
+    pub const WEEKDAYS: [Weekday; 7] = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+
+    pub fn day(&self, day: Weekday) -> &[TimeRange] {
+        match day {
+            Weekday::Mon => &self.monday,
+            Weekday::Tue => &self.tuesday,
+            Weekday::Wed => &self.wednesday,
+            Weekday::Thu => &self.thursday,
+            Weekday::Fri => &self.friday,
+            Weekday::Sat => &self.saturday,
+            Weekday::Sun => &self.sunday,
+        }
+    }
+
+    pub fn day_mut(&mut self, day: Weekday) -> &mut Vec<TimeRange> {
+        match day {
+            Weekday::Mon => &mut self.monday,
+            Weekday::Tue => &mut self.tuesday,
+            Weekday::Wed => &mut self.wednesday,
+            Weekday::Thu => &mut self.thursday,
+            Weekday::Fri => &mut self.friday,
+            Weekday::Sat => &mut self.saturday,
+            Weekday::Sun => &mut self.sunday,
+        }
+    }
+
+    /// Whether reminders may fire at local time `at`.
+    pub fn is_active(&self, at: NaiveDateTime) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        let time = at.time();
+        let today = self.day(at.weekday()).iter().any(|range| {
+            if range.wraps_midnight() {
+                time >= range.start
+            } else {
+                range.start <= time && time < range.end
+            }
+        });
+        // Ranges from yesterday that run past midnight
+        let spill_over = self
+            .day(at.weekday().pred())
+            .iter()
+            .any(|range| range.wraps_midnight() && time < range.end);
+        today || spill_over
+    }
+
+    /// The first time after `after` at which [`is_active`](Self::is_active)
+    /// may change, or `None` if the schedule is disabled or empty.
+    pub fn next_change(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        if !self.enabled {
+            return None;
+        }
+        // Yesterday's overnight ranges can end today; look a full week ahead
+        (0..=8u64)
+            .filter_map(|offset| (after.date() - Days::new(1)).checked_add_days(Days::new(offset)))
+            .flat_map(|date| {
+                self.day(date.weekday()).iter().flat_map(move |range| {
+                    let start = date.and_time(range.start);
+                    let end_date = if range.wraps_midnight() {
+                        date.succ_opt().unwrap_or(date)
+                    } else {
+                        date
+                    };
+                    [start, end_date.and_time(range.end)]
+                })
+            })
+            .filter(|boundary| *boundary > after)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// Local time on day `day` of January 2024, which starts on a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn time(hour: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn office_hours() -> WeeklySchedule {
+        WeeklySchedule {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    /// Enabled, but without a single range.
+    fn no_hours() -> WeeklySchedule {
+        let mut schedule = office_hours();
+        for day in WeeklySchedule::WEEKDAYS {
+            schedule.day_mut(day).clear();
+        }
+        schedule
+    }
+
+    #[test]
+    fn weekday_ranges_include_their_start_but_not_their_end() {
+        let schedule = office_hours();
+        assert!(!schedule.is_active(at(1, 8, 59)));
+        assert!(schedule.is_active(at(1, 9, 0)));
+        assert!(schedule.is_active(at(3, 16, 59)));
+        assert!(!schedule.is_active(at(5, 17, 0)));
+    }
+
+    #[test]
+    fn days_without_ranges_are_off() {
+        let schedule = office_hours();
+        assert!(!schedule.is_active(at(6, 12, 0)));
+        assert!(!schedule.is_active(at(7, 12, 0)));
+        // ...unless the schedule is switched off
+        let schedule = WeeklySchedule::default();
+        assert!(schedule.is_active(at(7, 12, 0)));
+        assert_eq!(schedule.next_change(at(7, 12, 0)), None);
+    }
+
+    #[test]
+    fn ranges_can_run_past_midnight() {
+        let mut schedule = office_hours();
+        schedule.friday = vec![TimeRange::new(time(22), time(2))];
+        assert!(!schedule.is_active(at(5, 21, 0)));
+        assert!(schedule.is_active(at(5, 23, 0)));
+        assert!(schedule.is_active(at(6, 1, 59)));
+        assert!(!schedule.is_active(at(6, 2, 0)));
+        // The range belongs to Friday: Thursday night is off
+        assert!(!schedule.is_active(at(5, 1, 0)));
+        assert_eq!(schedule.next_change(at(5, 23, 0)), Some(at(6, 2, 0)));
+    }
+
+    #[test]
+    fn next_change_looks_across_the_weekend() {
+        let schedule = office_hours();
+        assert_eq!(schedule.next_change(at(1, 8, 0)), Some(at(1, 9, 0)));
+        assert_eq!(schedule.next_change(at(1, 9, 0)), Some(at(1, 17, 0)));
+        assert_eq!(schedule.next_change(at(5, 17, 30)), Some(at(8, 9, 0)));
+
+        // A single range comes round again a week later
+        let mut schedule = no_hours();
+        schedule.monday = vec![TimeRange::new(time(9), time(17))];
+        assert_eq!(schedule.next_change(at(1, 18, 0)), Some(at(8, 9, 0)));
+    }
+
+    #[test]
+    fn an_empty_schedule_never_changes() {
+        let schedule = no_hours();
+        assert!(!schedule.is_active(at(1, 12, 0)));
+        assert_eq!(schedule.next_change(at(1, 12, 0)), None);
+    }
+}
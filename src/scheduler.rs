@@ -0,0 +1,1307 @@
+use crate::calendar::Calendar;
+use crate::pomodoro::{Pomodoro, PomodoroPhase, PomodoroStatus};
+use crate::reminder::{
+    EscalationLevel, ReminderDefinition, ReminderOutcome, ReminderType, SNOOZE_LENGTH,
+};
+use crate::schedule::WeeklySchedule;
+use crate::shared_state::BlinkSettings;
+use chrono::{Local, NaiveDateTime};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Source of "now" for the scheduler, so tests can drive time by hand.
+pub trait Clock {
+    /// Monotonic time, used for countdowns.
+    fn now(&self) -> Instant;
+    /// Local wall-clock time, used for the working-hours schedule.
+    fn local_now(&self) -> NaiveDateTime;
+    /// Time since boot including time spent suspended, if the platform has
+    /// such a clock. Compared with [`now`](Self::now) to notice a suspend,
+    /// so only the difference between two readings matters.
+    fn since_boot(&self) -> Option<Duration>;
+}
+
+/// The real monotonic clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn local_now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn since_boot(&self) -> Option<Duration> {
+        let mut time = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: `time` is a valid timespec for clock_gettime to write to.
+        let ok = unsafe { libc::clock_gettime(libc::CLOCK_BOOTTIME, &mut time) } == 0;
+        ok.then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+    }
+
+    /// Without a boot clock, the wall clock stands in: it too keeps running
+    /// while suspended. Clock adjustments can look like a suspend, but only
+    /// large ones, as gaps under [`MIN_SUSPEND_GAP`] are ignored.
+    #[cfg(not(target_os = "linux"))]
+    fn since_boot(&self) -> Option<Duration> {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct FakeClock {
+    start: Instant,
+    local_start: NaiveDateTime,
+    now: std::rc::Rc<std::cell::Cell<Instant>>,
+    /// Total time spent in [`suspend`](Self::suspend).
+    suspended: std::rc::Rc<std::cell::Cell<Duration>>,
+}
+
+#[cfg(test)]
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::at(Local::now().naive_local())
+    }
+}
+
+#[cfg(test)]
+impl FakeClock {
+    /// A fake clock whose wall-clock time starts at `local`.
+    pub fn at(local: NaiveDateTime) -> Self {
+        let start = Instant::now();
+        Self {
+            start,
+            local_start: local,
+            now: std::rc::Rc::new(std::cell::Cell::new(start)),
+            suspended: std::rc::Rc::default(),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+
+    /// Sleep like a suspended machine: wall-clock and boot time move
+    /// on, the monotonic clock doesn't.
+    pub fn suspend(&self, by: Duration) {
+        self.suspended.set(self.suspended.get() + by);
+    }
+}
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    fn local_now(&self) -> NaiveDateTime {
+        self.local_start + (self.now.get() - self.start) + self.suspended.get()
+    }
+
+    fn since_boot(&self) -> Option<Duration> {
+        Some(self.now.get() - self.start + self.suspended.get())
+    }
+}
+
+/// Shortest gap between the monotonic and boot clocks that counts as a
+/// suspend, so clock drift or an NTP correction is never mistaken for one.
+const MIN_SUSPEND_GAP: Duration = Duration::from_secs(10);
+
+/// Longest the scheduler waits before looking at the wall clock again
+/// during a timed pause, as a suspend stops the monotonic clock its
+/// deadlines are measured on.
+const PAUSE_RECHECK: Duration = Duration::from_secs(60);
+
+/// Largest accepted jitter percentage, so intervals never shrink to zero.
+pub const MAX_JITTER: u8 = 90;
+
+/// Emitted by the scheduler when a reminder should be shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderEvent {
+    Due {
+        kind: ReminderType,
+        duration: u64,
+        level: EscalationLevel,
+    },
+}
+
+/// A running countdown, as reported by [`ReminderScheduler::countdowns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Countdown {
+    pub kind: ReminderType,
+    /// When it started. Stays put when it is snoozed or frozen.
+    pub started: Instant,
+    /// When it runs out, before anything holds the popup back.
+    pub due: Instant,
+}
+
+/// Instructions for the scheduler from the rest of the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulerCommand {
+    /// Stop every countdown, for the given time or until [`SchedulerCommand::Resume`].
+    Pause(Option<Duration>),
+    /// End a pause and restart every countdown.
+    Resume,
+    /// The user answered a reminder window.
+    Respond {
+        kind: ReminderType,
+        outcome: ReminderOutcome,
+    },
+}
+
+/// How long a user-requested pause lasts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pause {
+    /// Until a local wall-clock time, which unlike the monotonic clock keeps
+    /// moving while the machine is suspended.
+    Until(NaiveDateTime),
+    Indefinitely,
+}
+
+/// Configured timing for a single reminder type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Timing {
+    enabled: bool,
+    interval: Duration,
+    duration: u64,
+    /// Maximum random deviation from `interval`, in percent.
+    jitter: u8,
+}
+
+/// Countdown state for a single reminder type.
+#[derive(Debug, Clone)]
+struct Slot {
+    kind: ReminderType,
+    timing: Timing,
+    /// When the current countdown started. Unlike `last_trigger`, not moved
+    /// by snoozes or idle time.
+    started: Instant,
+    last_trigger: Instant,
+    /// `timing.interval` with jitter applied, picked when the countdown started.
+    next_interval: Duration,
+    /// Reminders in a row that were ignored or skipped.
+    ignored: u32,
+}
+
+impl Slot {
+    fn new(kind: ReminderType, timing: Timing, now: Instant, rng: &mut StdRng) -> Self {
+        Self {
+            kind,
+            timing,
+            started: now,
+            last_trigger: now,
+            next_interval: jittered(timing.interval, timing.jitter, rng),
+            ignored: 0,
+        }
+    }
+
+    /// Start a fresh countdown at `now`.
+    fn restart(&mut self, now: Instant, rng: &mut StdRng) {
+        self.started = now;
+        self.last_trigger = now;
+        self.next_interval = jittered(self.timing.interval, self.timing.jitter, rng);
+    }
+
+    /// When the countdown runs out, or `None` if the reminder is disabled.
+    fn due_at(&self) -> Option<Instant> {
+        self.last_trigger
+            .checked_add(self.next_interval)
+            .filter(|_| self.timing.enabled)
+    }
+}
+
+/// Owns every reminder deadline and reports which ones have come due.
+///
+/// The scheduler does no I/O and never sleeps; the caller decides how often
+/// to call [`ReminderScheduler::poll`] and what to do with the events.
+pub struct ReminderScheduler<C: Clock = SystemClock> {
+    clock: C,
+    slots: Vec<Slot>,
+    idle_threshold: Duration,
+    idle_break_threshold: Duration,
+    idle_break_resets_blink: bool,
+    /// Ignored reminders in a row before escalating (0 = never).
+    escalate_after: u32,
+    fullscreen_after: u32,
+    /// When the current idle period began, if the user is idle.
+    idle_since: Option<Instant>,
+    defer_during_fullscreen: bool,
+    /// Longest a popup is held back by a fullscreen app (zero = until it ends).
+    max_fullscreen_deferral: Duration,
+    /// Whether the active window was fullscreen at the last check.
+    fullscreen: bool,
+    /// Whether the desktop was in Do Not Disturb mode at the last check.
+    dnd: bool,
+    /// Reminder types whose popups wait for Do Not Disturb to end.
+    respect_dnd: HashSet<ReminderType>,
+    /// Busy events that hold back popups.
+    calendar: Calendar,
+    /// End of the meeting in progress at the last poll.
+    meeting_until: Option<NaiveDateTime>,
+    /// Seconds of post-meeting break, or `None` to skip it.
+    post_meeting_break: Option<u64>,
+    pause: Option<Pause>,
+    schedule: WeeklySchedule,
+    /// Whether the last poll fell outside the working-hours schedule.
+    off_hours: bool,
+    pomodoro: Option<Pomodoro>,
+    /// Monotonic and boot time at the last poll, to notice a suspend.
+    last_seen: Option<(Instant, Duration)>,
+    rng: StdRng,
+}
+
+impl<C: Clock> ReminderScheduler<C> {
+    /// Create a scheduler whose countdowns all start now.
+    pub fn new(clock: C, settings: &BlinkSettings) -> Self {
+        Self::with_rng(clock, settings, StdRng::from_entropy())
+    }
+
+    /// Like [`new`](Self::new), but with reproducible interval jitter.
+    #[cfg(test)]
+    pub fn with_seed(clock: C, settings: &BlinkSettings, seed: u64) -> Self {
+        Self::with_rng(clock, settings, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(clock: C, settings: &BlinkSettings, rng: StdRng) -> Self {
+        let mut scheduler = Self {
+            clock,
+            slots: Vec::new(),
+            idle_threshold: Duration::ZERO,
+            idle_break_threshold: Duration::ZERO,
+            idle_break_resets_blink: false,
+            escalate_after: 0,
+            fullscreen_after: 0,
+            idle_since: None,
+            defer_during_fullscreen: false,
+            max_fullscreen_deferral: Duration::ZERO,
+            fullscreen: false,
+            dnd: false,
+            respect_dnd: HashSet::new(),
+            calendar: Calendar::default(),
+            meeting_until: None,
+            post_meeting_break: None,
+            pause: None,
+            schedule: WeeklySchedule::default(),
+            off_hours: false,
+            pomodoro: None,
+            last_seen: None,
+            rng,
+        };
+        scheduler.apply_settings(settings);
+        scheduler
+    }
+
+    /// Pick up new intervals and durations without restarting the countdowns.
+    /// A reminder that was just added or switched on starts its countdown now.
+    pub fn apply_settings(&mut self, settings: &BlinkSettings) {
+        let now = self.clock.now();
+        let definitions = settings.reminder_definitions();
+        self.slots.retain(|slot| {
+            definitions
+                .iter()
+                .any(|definition| definition.kind == slot.kind)
+        });
+        for definition in definitions {
+            let (kind, timing) = (definition.kind, Timing::from(definition));
+            match self.slots.iter_mut().find(|slot| slot.kind == kind) {
+                Some(slot) => {
+                    let switched_on = timing.enabled && !slot.timing.enabled;
+                    let reroll = timing.interval != slot.timing.interval
+                        || timing.jitter != slot.timing.jitter;
+                    slot.timing = timing;
+                    if switched_on {
+                        slot.restart(now, &mut self.rng);
+                    } else if reroll {
+                        slot.next_interval =
+                            jittered(timing.interval, timing.jitter, &mut self.rng);
+                    }
+                }
+                None => self.slots.push(Slot::new(kind, timing, now, &mut self.rng)),
+            }
+        }
+        self.idle_threshold = Duration::from_secs(settings.idle_threshold);
+        self.idle_break_threshold = Duration::from_secs(settings.idle_break_threshold);
+        self.idle_break_resets_blink = settings.idle_break_resets_blink;
+        self.schedule = settings.schedule.clone();
+        self.defer_during_fullscreen = settings.defer_during_fullscreen;
+        self.max_fullscreen_deferral = Duration::from_secs(settings.max_fullscreen_deferral);
+        self.respect_dnd = settings
+            .all_reminder_kinds()
+            .into_iter()
+            .chain([ReminderType::PomodoroBreak { long: true }])
+            .filter(|&kind| settings.delivery_for(kind).respect_dnd)
+            .collect();
+        self.post_meeting_break = settings
+            .post_meeting_break
+            .then_some(settings.post_meeting_break_duration);
+        self.escalate_after = settings.escalate_after;
+        self.fullscreen_after = settings.fullscreen_after;
+        match (&mut self.pomodoro, settings.pomodoro.enabled) {
+            (Some(pomodoro), true) => pomodoro.apply_settings(settings.pomodoro.clone()),
+            (None, true) => self.pomodoro = Some(Pomodoro::new(settings.pomodoro.clone(), now)),
+            (_, false) => self.pomodoro = None,
+        }
+    }
+
+    /// Current Pomodoro phase and when it ends, if Pomodoro mode is on.
+    pub fn pomodoro_status(&self) -> Option<PomodoroStatus> {
+        self.pomodoro.as_ref().map(Pomodoro::status)
+    }
+
+    /// Apply a command from the tray or a reminder window.
+    pub fn handle(&mut self, command: SchedulerCommand) {
+        match command {
+            SchedulerCommand::Pause(Some(length)) => {
+                self.pause = Some(Pause::Until(self.clock.local_now() + length));
+            }
+            SchedulerCommand::Pause(None) => self.pause = Some(Pause::Indefinitely),
+            SchedulerCommand::Resume => self.resume(),
+            SchedulerCommand::Respond { kind, outcome } => self.respond(kind, outcome),
+        }
+    }
+
+    /// Reschedule `kind` after the user answered its reminder.
+    ///
+    /// "Done" restarts the countdown from the end of the break, "Snooze"
+    /// brings the reminder back after [`SNOOZE_LENGTH`] and "Skip" leaves
+    /// the countdown that started when the reminder fired untouched.
+    /// Skipped reminders count towards escalation, and so do ignored ones
+    /// unless the type [closes itself](ReminderType::closes_itself); "Done"
+    /// resets it.
+    /// Pomodoro breaks end early on "Done" or "Skip"; "Snooze" postpones them.
+    fn respond(&mut self, kind: ReminderType, outcome: ReminderOutcome) {
+        let now = self.clock.now();
+        if let ReminderType::PomodoroBreak { .. } = kind {
+            if let Some(pomodoro) = &mut self.pomodoro {
+                match outcome {
+                    ReminderOutcome::Snoozed => pomodoro.postpone_break(now, SNOOZE_LENGTH),
+                    ReminderOutcome::Done | ReminderOutcome::Skipped => {
+                        if pomodoro.phase().is_break() {
+                            pomodoro.restart_work(now);
+                            for slot in &mut self.slots {
+                                slot.restart(now, &mut self.rng);
+                            }
+                        }
+                    }
+                    ReminderOutcome::Ignored => {}
+                }
+            }
+            return;
+        }
+        let Some(slot) = self.slots.iter_mut().find(|slot| slot.kind == kind) else {
+            return;
+        };
+        match outcome {
+            ReminderOutcome::Done => {
+                slot.restart(now, &mut self.rng);
+                slot.ignored = 0;
+            }
+            ReminderOutcome::Snoozed => {
+                slot.last_trigger = (now + SNOOZE_LENGTH)
+                    .checked_sub(slot.next_interval)
+                    .unwrap_or(now);
+            }
+            ReminderOutcome::Ignored if kind.closes_itself() => {}
+            ReminderOutcome::Skipped | ReminderOutcome::Ignored => {
+                slot.ignored = slot.ignored.saturating_add(1);
+            }
+        }
+    }
+
+    /// Whether the user has paused reminders.
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_some()
+    }
+
+    /// End a pause. A pause is a break in itself, so every countdown starts over.
+    fn resume(&mut self) {
+        if self.pause.take().is_some() {
+            self.restart_all();
+        }
+    }
+
+    /// Start every countdown, and the Pomodoro work session, over from now.
+    fn restart_all(&mut self) {
+        let now = self.clock.now();
+        for slot in &mut self.slots {
+            slot.restart(now, &mut self.rng);
+        }
+        if let Some(pomodoro) = &mut self.pomodoro {
+            pomodoro.restart_work(now);
+        }
+    }
+
+    /// How long the machine was suspended since the last call: the boot
+    /// clock keeps counting during a suspend, the monotonic clock doesn't.
+    fn take_suspended_time(&mut self) -> Duration {
+        let now = self.clock.now();
+        let Some(boot) = self.clock.since_boot() else {
+            return Duration::ZERO;
+        };
+        match self.last_seen.replace((now, boot)) {
+            Some((last_now, last_boot)) => boot
+                .saturating_sub(last_boot)
+                .saturating_sub(now.saturating_duration_since(last_now)),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Whether the user was away at the last idle check, long enough to
+    /// freeze the countdowns or to count as a break.
+    pub fn is_idle(&self) -> bool {
+        self.idle_since.is_some()
+    }
+
+    /// Whether the countdowns are frozen because the user has been away for
+    /// the idle threshold.
+    fn is_frozen(&self) -> bool {
+        let now = self.clock.now();
+        self.idle_since.is_some_and(|since| {
+            !self.idle_threshold.is_zero()
+                && now.saturating_duration_since(since) >= self.idle_threshold
+        })
+    }
+
+    /// Feed in the latest idle time from an [`IdleSource`](crate::idle::IdleSource).
+    ///
+    /// Once `idle_for` reaches the idle threshold the countdowns freeze,
+    /// backdated to when input actually stopped. They resume with the same
+    /// remaining time as soon as the user is active again, unless the idle
+    /// period was long enough to count as a break, in which case the posture
+    /// (and optionally blink) countdown starts over. Breaks count even when
+    /// freezing is switched off.
+    pub fn observe_idle(&mut self, idle_for: Option<Duration>) {
+        let now = self.clock.now();
+        // Away long enough to matter for either threshold
+        let noticed = [self.idle_threshold, self.idle_break_threshold]
+            .into_iter()
+            .filter(|threshold| !threshold.is_zero())
+            .min();
+        let idle_for =
+            idle_for.filter(|idle_for| noticed.is_some_and(|threshold| *idle_for >= threshold));
+        match (idle_for, self.idle_since) {
+            (Some(idle_for), None) => {
+                self.idle_since = Some(now.checked_sub(idle_for).unwrap_or(now));
+            }
+            (None, Some(since)) => {
+                let away = now.duration_since(since);
+                self.idle_since = None;
+                let was_break =
+                    !self.idle_break_threshold.is_zero() && away >= self.idle_break_threshold;
+                let was_frozen = !self.idle_threshold.is_zero() && away >= self.idle_threshold;
+                for slot in &mut self.slots {
+                    let resets = match slot.kind {
+                        ReminderType::Posture | ReminderType::EyeBreak => was_break,
+                        ReminderType::Blink => was_break && self.idle_break_resets_blink,
+                        ReminderType::Custom(_)
+                        | ReminderType::MeetingBreak
+                        | ReminderType::PomodoroBreak { .. } => false,
+                    };
+                    if resets {
+                        slot.restart(now, &mut self.rng);
+                    } else if was_frozen {
+                        let active = since.saturating_duration_since(slot.last_trigger);
+                        slot.last_trigger = now.checked_sub(active).unwrap_or(now);
+                    }
+                }
+                if was_break {
+                    if let Some(pomodoro) = &mut self.pomodoro {
+                        pomodoro.restart_work(now);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Feed in the latest answer from a [`FocusGuard`](crate::focus::FocusGuard).
+    ///
+    /// While a fullscreen app is in front, popups that come due are held back
+    /// until it goes away or the maximum deferral has passed.
+    pub fn observe_fullscreen(&mut self, fullscreen: bool) {
+        self.fullscreen = self.defer_during_fullscreen && fullscreen;
+    }
+
+    /// Whether due popups are being held back for a fullscreen app.
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    /// Feed in the latest value watched from a [`DndSource`](crate::dnd::DndSource).
+    ///
+    /// While Do Not Disturb is on, popups of the types that respect it are
+    /// queued and shown once it is switched off.
+    pub fn observe_dnd(&mut self, dnd: bool) {
+        self.dnd = dnd;
+    }
+
+    /// Replace the busy events, e.g. after an `.ics` file changed.
+    pub fn set_calendar(&mut self, calendar: Calendar) {
+        self.calendar = calendar;
+    }
+
+    /// Whether a calendar meeting was in progress at the last poll.
+    pub fn in_meeting(&self) -> bool {
+        self.meeting_until.is_some()
+    }
+
+    /// How long after coming due a `kind` popup may currently be held back.
+    /// Meetings and Do Not Disturb hold popups back until they end.
+    fn deferral(&self, kind: ReminderType) -> Duration {
+        if self.in_meeting() || (self.dnd && self.respect_dnd.contains(&kind)) {
+            return Duration::MAX;
+        }
+        match (self.fullscreen, self.max_fullscreen_deferral) {
+            (false, _) => Duration::ZERO,
+            (true, max) if max.is_zero() => Duration::MAX,
+            (true, max) => max,
+        }
+    }
+
+    /// The next moment [`poll`](Self::poll) could have something to do, or
+    /// `None` if nothing will happen until a command or new settings arrive.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let now = self.clock.now();
+        let local_now = self.clock.local_now();
+        let to_instant = |at: NaiveDateTime| {
+            (at - local_now)
+                .to_std()
+                .ok()
+                .and_then(|until| now.checked_add(until))
+        };
+        let schedule_change = self.schedule.next_change(local_now).and_then(to_instant);
+        let meeting_change = self.calendar.next_change(local_now).and_then(to_instant);
+        let pause_end = match self.pause {
+            Some(Pause::Until(end)) => {
+                Some(to_instant(end).unwrap_or(now).min(now + PAUSE_RECHECK))
+            }
+            _ => None,
+        };
+        let (reminder_due, phase_end) =
+            if self.is_frozen() || self.is_paused() || self.is_off_hours() {
+                (None, None)
+            } else {
+                let on_break = self
+                    .pomodoro
+                    .as_ref()
+                    .is_some_and(|pomodoro| pomodoro.phase().is_break());
+                let reminder_due = self
+                    .slots
+                    .iter()
+                    .filter(|_| !on_break)
+                    .filter_map(|slot| slot.due_at()?.checked_add(self.deferral(slot.kind)))
+                    .min();
+                (
+                    reminder_due,
+                    self.pomodoro
+                        .as_ref()
+                        .and_then(|pomodoro| phase_release(pomodoro, self.deferral(BREAK))),
+                )
+            };
+        [
+            reminder_due,
+            phase_end,
+            pause_end,
+            schedule_change,
+            meeting_change,
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// Every enabled countdown.
+    pub fn countdowns(&self) -> Vec<Countdown> {
+        self.slots
+            .iter()
+            .filter_map(|slot| {
+                Some(Countdown {
+                    kind: slot.kind,
+                    started: slot.started,
+                    due: slot.due_at()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Reminders that have come due but are being held back by a fullscreen
+    /// app, a meeting or Do Not Disturb.
+    pub fn held_back(&self) -> Vec<ReminderType> {
+        if self.is_frozen() || self.is_paused() || self.is_off_hours() {
+            return Vec::new();
+        }
+        let now = self.clock.now();
+        let mut held: Vec<ReminderType> = self
+            .slots
+            .iter()
+            .filter(|slot| slot.due_at().is_some_and(|due| now >= due))
+            .filter(|slot| !self.deferral(slot.kind).is_zero())
+            .map(|slot| slot.kind)
+            .collect();
+        if let Some(pomodoro) = &self.pomodoro {
+            if !pomodoro.phase().is_break()
+                && now >= pomodoro.phase_end()
+                && !self.deferral(BREAK).is_zero()
+            {
+                held.push(BREAK);
+            }
+        }
+        held
+    }
+
+    /// Whether the current time is outside the working-hours schedule.
+    pub fn is_off_hours(&self) -> bool {
+        self.off_hours
+    }
+
+    /// Return every reminder that is due and restart its countdown.
+    ///
+    /// Nothing fires outside the working-hours schedule, and every countdown
+    /// starts over when a scheduled range begins or the machine wakes from
+    /// suspend. Popups wait for calendar meetings to end, which may bring a
+    /// post-meeting break, and for Do Not Disturb to be switched off.
+    pub fn poll(&mut self) -> Vec<ReminderEvent> {
+        // Whatever came due while the machine slept starts over instead of
+        // firing all at once
+        if self.take_suspended_time() >= MIN_SUSPEND_GAP {
+            self.restart_all();
+        }
+        let in_hours = self.schedule.is_active(self.clock.local_now());
+        if in_hours && self.off_hours {
+            self.restart_all();
+        }
+        self.off_hours = !in_hours;
+
+        if let Some(Pause::Until(end)) = self.pause {
+            if self.clock.local_now() >= end {
+                self.resume();
+            }
+        }
+        let meeting_until = self.calendar.busy_until(self.clock.local_now());
+        let meeting_ended = self.in_meeting() && meeting_until.is_none();
+        self.meeting_until = meeting_until;
+        let now = self.clock.now();
+        let mut events = Vec::new();
+        if self.is_frozen() || self.is_paused() || self.is_off_hours() {
+            return events;
+        }
+        // A break held back by Do Not Disturb would be stale by the time it
+        // ends, so it is dropped rather than queued
+        let meeting_break = self
+            .post_meeting_break
+            .filter(|_| self.deferral(ReminderType::MeetingBreak) != Duration::MAX);
+        if meeting_ended {
+            if let Some(duration) = meeting_break {
+                events.push(ReminderEvent::Due {
+                    kind: ReminderType::MeetingBreak,
+                    duration,
+                    level: EscalationLevel::Subtle,
+                });
+                // The break covers everything that was held back
+                for slot in &mut self.slots {
+                    slot.restart(now, &mut self.rng);
+                }
+            }
+        }
+        let deferrals: Vec<Duration> = self
+            .slots
+            .iter()
+            .map(|slot| self.deferral(slot.kind))
+            .collect();
+        let break_deferral = self.deferral(BREAK);
+        // Pomodoro breaks replace the other reminders until the break is over
+        if let Some(pomodoro) = &mut self.pomodoro {
+            let released = phase_release(pomodoro, break_deferral).is_some_and(|at| now >= at);
+            match released.then(|| pomodoro.advance(now)).flatten() {
+                Some(PomodoroPhase::Work) => {
+                    for slot in &mut self.slots {
+                        slot.restart(now, &mut self.rng);
+                    }
+                }
+                Some(phase) => events.push(ReminderEvent::Due {
+                    kind: ReminderType::PomodoroBreak {
+                        long: phase == PomodoroPhase::LongBreak,
+                    },
+                    duration: pomodoro.phase_length().as_secs(),
+                    level: EscalationLevel::Subtle,
+                }),
+                None => {}
+            }
+            if pomodoro.phase().is_break() {
+                return events;
+            }
+        }
+        for (slot, deferral) in self.slots.iter_mut().zip(deferrals) {
+            let released = slot
+                .due_at()
+                .and_then(|due| due.checked_add(deferral))
+                .is_some_and(|at| now >= at);
+            if released {
+                events.push(ReminderEvent::Due {
+                    kind: slot.kind,
+                    duration: slot.timing.duration,
+                    level: EscalationLevel::after(
+                        slot.ignored,
+                        self.escalate_after,
+                        self.fullscreen_after,
+                    ),
+                });
+                slot.restart(now, &mut self.rng);
+            }
+        }
+        events
+    }
+}
+
+/// Stands in for both Pomodoro breaks, which share their settings.
+const BREAK: ReminderType = ReminderType::PomodoroBreak { long: false };
+
+/// When the current Pomodoro phase may end: a work session can be held back
+/// by `deferral` so its break doesn't pop up over a fullscreen app.
+fn phase_release(pomodoro: &Pomodoro, deferral: Duration) -> Option<Instant> {
+    if pomodoro.phase().is_break() {
+        Some(pomodoro.phase_end())
+    } else {
+        pomodoro.phase_end().checked_add(deferral)
+    }
+}
+
+impl From<ReminderDefinition> for Timing {
+    fn from(definition: ReminderDefinition) -> Self {
+        Timing {
+            enabled: definition.enabled,
+            interval: interval(definition.interval, definition.duration),
+            duration: definition.duration,
+            jitter: definition.jitter,
+        }
+    }
+}
+
+/// An interval of `seconds`, but never shorter than the popup it brings
+/// up stays open (`duration`) nor than a second, so that a zero in the
+/// settings can't make reminders fire back to back.
+fn interval(seconds: u64, duration: u64) -> Duration {
+    Duration::from_secs(seconds.max(duration).max(1))
+}
+
+/// Pick an interval uniformly within `jitter` percent of `interval`.
+fn jittered(interval: Duration, jitter: u8, rng: &mut StdRng) -> Duration {
+    if jitter == 0 {
+        return interval;
+    }
+    let spread = f64::from(jitter.min(MAX_JITTER)) / 100.0;
+    let factor = rng.gen_range(1.0 - spread..=1.0 + spread);
+    Duration::try_from_secs_f64(interval.as_secs_f64() * factor).unwrap_or(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pomodoro::PomodoroSettings;
+    use crate::test_support::{kinds, settings, SECOND};
+
+    /// A scheduler whose blink reminder has just fired, 30 s in.
+    fn fired() -> (FakeClock, ReminderScheduler<FakeClock>) {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        clock.advance(30 * SECOND);
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::Blink]);
+        (clock, scheduler)
+    }
+
+    #[test]
+    fn first_reminder_fires_after_its_interval() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 30 * SECOND));
+        clock.advance(29 * SECOND);
+        assert!(scheduler.poll().is_empty());
+        clock.advance(SECOND);
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::Blink]);
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 30 * SECOND));
+    }
+
+    #[test]
+    fn reminders_due_together_fire_together() {
+        let clock = FakeClock::default();
+        let settings = BlinkSettings {
+            blink_interval: 60,
+            posture_interval: 60,
+            ..Default::default()
+        };
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings);
+        clock.advance(60 * SECOND);
+        assert_eq!(
+            kinds(scheduler.poll()),
+            [ReminderType::Blink, ReminderType::Posture]
+        );
+    }
+
+    #[test]
+    fn new_interval_moves_the_deadline_without_restarting() {
+        let clock = FakeClock::default();
+        let start = clock.now();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        clock.advance(5 * SECOND);
+        scheduler.apply_settings(&BlinkSettings {
+            blink_interval: 10,
+            ..settings()
+        });
+        assert_eq!(scheduler.next_deadline(), Some(start + 10 * SECOND));
+        scheduler.apply_settings(&BlinkSettings {
+            blink_interval: 60,
+            ..settings()
+        });
+        assert_eq!(scheduler.next_deadline(), Some(start + 60 * SECOND));
+    }
+
+    #[test]
+    fn reminder_switched_on_starts_counting_now() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(
+            clock.clone(),
+            &BlinkSettings {
+                blink_interval: 3600,
+                ..settings()
+            },
+        );
+        clock.advance(100 * SECOND);
+        scheduler.apply_settings(&BlinkSettings {
+            blink_interval: 3600,
+            eye_break_enabled: true,
+            eye_break_interval: 20,
+            ..settings()
+        });
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 20 * SECOND));
+        clock.advance(20 * SECOND);
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::EyeBreak]);
+    }
+
+    #[test]
+    fn done_restarts_the_countdown_from_the_answer() {
+        let (clock, mut scheduler) = fired();
+        clock.advance(10 * SECOND);
+        scheduler.handle(SchedulerCommand::Respond {
+            kind: ReminderType::Blink,
+            outcome: ReminderOutcome::Done,
+        });
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 30 * SECOND));
+    }
+
+    #[test]
+    fn snooze_brings_the_reminder_back_after_the_snooze_length() {
+        let (clock, mut scheduler) = fired();
+        clock.advance(10 * SECOND);
+        scheduler.handle(SchedulerCommand::Respond {
+            kind: ReminderType::Blink,
+            outcome: ReminderOutcome::Snoozed,
+        });
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + SNOOZE_LENGTH));
+    }
+
+    #[test]
+    fn skip_keeps_the_countdown_that_started_when_it_fired() {
+        let (clock, mut scheduler) = fired();
+        let fired_at = clock.now();
+        clock.advance(10 * SECOND);
+        scheduler.handle(SchedulerCommand::Respond {
+            kind: ReminderType::Blink,
+            outcome: ReminderOutcome::Skipped,
+        });
+        assert_eq!(scheduler.next_deadline(), Some(fired_at + 30 * SECOND));
+    }
+
+    /// Fire `kind` after `interval` seconds, answer it with `outcome` and
+    /// return the escalation level of the next one.
+    fn level_after_answers(
+        kind: ReminderType,
+        interval: u64,
+        outcome: ReminderOutcome,
+        answers: usize,
+    ) -> EscalationLevel {
+        let clock = FakeClock::default();
+        let settings = BlinkSettings {
+            blink_interval: interval,
+            posture_interval: interval,
+            escalate_after: 3,
+            fullscreen_after: 6,
+            ..Default::default()
+        };
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings);
+        for _ in 0..answers {
+            clock.advance(Duration::from_secs(interval));
+            scheduler.poll();
+            scheduler.handle(SchedulerCommand::Respond { kind, outcome });
+        }
+        clock.advance(Duration::from_secs(interval));
+        scheduler
+            .poll()
+            .into_iter()
+            .find_map(
+                |ReminderEvent::Due {
+                     kind: due, level, ..
+                 }| (due == kind).then_some(level),
+            )
+            .expect("reminder did not fire")
+    }
+
+    #[test]
+    fn skipping_escalates() {
+        let level = |answers| {
+            level_after_answers(ReminderType::Blink, 30, ReminderOutcome::Skipped, answers)
+        };
+        assert_eq!(level(2), EscalationLevel::Subtle);
+        assert_eq!(level(3), EscalationLevel::Prominent);
+        assert_eq!(level(6), EscalationLevel::Fullscreen);
+    }
+
+    #[test]
+    fn self_closing_reminders_do_not_escalate_when_they_close() {
+        let level = level_after_answers(ReminderType::Blink, 30, ReminderOutcome::Ignored, 10);
+        assert_eq!(level, EscalationLevel::Subtle);
+    }
+
+    #[test]
+    fn unanswered_reminders_that_need_an_answer_escalate() {
+        assert!(!ReminderType::EyeBreak.closes_itself());
+        let clock = FakeClock::default();
+        let settings = BlinkSettings {
+            eye_break_enabled: true,
+            eye_break_interval: 60,
+            escalate_after: 1,
+            ..settings()
+        };
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings);
+        clock.advance(60 * SECOND);
+        scheduler.poll();
+        scheduler.handle(SchedulerCommand::Respond {
+            kind: ReminderType::EyeBreak,
+            outcome: ReminderOutcome::Ignored,
+        });
+        clock.advance(60 * SECOND);
+        let levels: Vec<EscalationLevel> = scheduler
+            .poll()
+            .into_iter()
+            .filter_map(|ReminderEvent::Due { kind, level, .. }| {
+                (kind == ReminderType::EyeBreak).then_some(level)
+            })
+            .collect();
+        assert_eq!(levels, [EscalationLevel::Prominent]);
+    }
+
+    #[test]
+    fn intervals_are_never_shorter_than_the_popup() {
+        let clock = FakeClock::default();
+        let start = clock.now();
+        let mut scheduler = ReminderScheduler::new(
+            clock.clone(),
+            &BlinkSettings {
+                blink_interval: 0,
+                blink_duration: 0,
+                ..settings()
+            },
+        );
+        assert_eq!(scheduler.next_deadline(), Some(start + SECOND));
+        scheduler.apply_settings(&BlinkSettings {
+            blink_interval: 2,
+            blink_duration: 5,
+            ..settings()
+        });
+        assert_eq!(scheduler.next_deadline(), Some(start + 5 * SECOND));
+    }
+
+    /// Seconds until the first blink of a scheduler seeded with `seed`.
+    fn first_blink(interval: u64, jitter: u8, seed: u64) -> u64 {
+        let clock = FakeClock::default();
+        let settings = BlinkSettings {
+            blink_interval: interval,
+            blink_jitter: jitter,
+            ..settings()
+        };
+        let scheduler = ReminderScheduler::with_seed(clock.clone(), &settings, seed);
+        (scheduler.next_deadline().unwrap() - clock.now()).as_secs()
+    }
+
+    #[test]
+    fn jitter_stays_within_its_spread() {
+        let picked: Vec<u64> = (0..200).map(|seed| first_blink(1000, 20, seed)).collect();
+        assert!(picked.iter().all(|secs| (800..=1200).contains(secs)));
+        // Spread out, not stuck on the interval
+        assert!(picked.iter().any(|&secs| secs < 950));
+        assert!(picked.iter().any(|&secs| secs > 1050));
+    }
+
+    #[test]
+    fn jitter_is_capped() {
+        let low = u64::from(100 - MAX_JITTER) * 10;
+        let high = u64::from(100 + MAX_JITTER) * 10;
+        for seed in 0..200 {
+            let secs = first_blink(1000, u8::MAX, seed);
+            assert!((low..=high).contains(&secs), "{secs} s");
+        }
+    }
+
+    #[test]
+    fn same_seed_same_intervals() {
+        assert_eq!(first_blink(1000, 50, 42), first_blink(1000, 50, 42));
+    }
+
+    #[test]
+    fn timed_pause_ends_on_the_wall_clock() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        scheduler.handle(SchedulerCommand::Pause(Some(3600 * SECOND)));
+        assert!(scheduler.next_deadline() <= Some(clock.now() + PAUSE_RECHECK));
+        clock.advance(3599 * SECOND);
+        assert!(scheduler.poll().is_empty());
+        assert!(scheduler.is_paused());
+        clock.advance(SECOND);
+        scheduler.poll();
+        assert!(!scheduler.is_paused());
+    }
+
+    #[test]
+    fn suspend_starts_every_countdown_over() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        clock.advance(20 * SECOND);
+        assert!(scheduler.poll().is_empty());
+        // The blink reminder comes due just after waking up
+        clock.suspend(3600 * SECOND);
+        clock.advance(10 * SECOND);
+        assert!(scheduler.poll().is_empty());
+        let due: Vec<_> = scheduler
+            .countdowns()
+            .into_iter()
+            .map(|countdown| (countdown.kind, countdown.due))
+            .collect();
+        assert_eq!(
+            due,
+            [
+                (ReminderType::Blink, clock.now() + 30 * SECOND),
+                (ReminderType::Posture, clock.now() + 3600 * SECOND),
+            ]
+        );
+    }
+
+    #[test]
+    fn short_clock_gaps_are_not_a_suspend() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        clock.advance(20 * SECOND);
+        scheduler.poll();
+        clock.suspend(MIN_SUSPEND_GAP - SECOND);
+        clock.advance(10 * SECOND);
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::Blink]);
+    }
+
+    #[test]
+    fn timed_pause_ends_while_suspended() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        scheduler.handle(SchedulerCommand::Pause(Some(3600 * SECOND)));
+        scheduler.poll();
+        clock.suspend(8 * 3600 * SECOND);
+        clock.advance(PAUSE_RECHECK);
+        scheduler.poll();
+        assert!(!scheduler.is_paused());
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 30 * SECOND));
+    }
+
+    #[test]
+    fn countdowns_wait_for_working_hours_and_start_fresh() {
+        // Monday 16:59:50, with office hours on weekdays from 9 to 5
+        let monday = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let clock = FakeClock::at(monday.and_hms_opt(16, 59, 50).unwrap());
+        let mut settings = settings();
+        settings.schedule.enabled = true;
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings);
+        clock.advance(10 * SECOND);
+        assert!(scheduler.poll().is_empty());
+        assert!(scheduler.is_off_hours());
+        assert!(scheduler.held_back().is_empty());
+        // The blink reminder would have been due 20 s later; it waits for Tuesday 9:00
+        let overnight = 16 * 3600 * SECOND;
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + overnight));
+
+        clock.advance(overnight);
+        assert!(scheduler.poll().is_empty());
+        assert!(!scheduler.is_off_hours());
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 30 * SECOND));
+        clock.advance(30 * SECOND);
+        assert_eq!(kinds(scheduler.poll()), [ReminderType::Blink]);
+    }
+
+    #[test]
+    fn pause_holds_everything_until_resumed() {
+        let clock = FakeClock::default();
+        let mut scheduler = ReminderScheduler::new(clock.clone(), &settings());
+        scheduler.handle(SchedulerCommand::Pause(None));
+        assert_eq!(scheduler.next_deadline(), None);
+        clock.advance(100 * SECOND);
+        assert!(scheduler.poll().is_empty());
+        scheduler.handle(SchedulerCommand::Resume);
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 30 * SECOND));
+    }
+
+    const MINUTE: Duration = Duration::from_secs(60);
+    const SHORT_BREAK: ReminderType = ReminderType::PomodoroBreak { long: false };
+    const LONG_BREAK: ReminderType = ReminderType::PomodoroBreak { long: true };
+
+    /// Pomodoro with 25 min of work, 5 min short and 15 min long breaks and a
+    /// long break after every second session. The other reminders are far
+    /// enough out not to interfere.
+    fn pomodoro(clock: &FakeClock) -> ReminderScheduler<FakeClock> {
+        ReminderScheduler::new(
+            clock.clone(),
+            &BlinkSettings {
+                blink_interval: 36000,
+                posture_interval: 36000,
+                pomodoro: PomodoroSettings {
+                    enabled: true,
+                    cycles_before_long_break: 2,
+                    ..Default::default()
+                },
+                ..settings()
+            },
+        )
+    }
+
+    /// The current Pomodoro phase and the time left in it.
+    fn phase(
+        clock: &FakeClock,
+        scheduler: &ReminderScheduler<FakeClock>,
+    ) -> (PomodoroPhase, Duration) {
+        let status = scheduler.pomodoro_status().unwrap();
+        (status.phase, status.ends_at - clock.now())
+    }
+
+    fn durations(events: &[ReminderEvent]) -> Vec<u64> {
+        events
+            .iter()
+            .map(|ReminderEvent::Due { duration, .. }| *duration)
+            .collect()
+    }
+
+    #[test]
+    fn pomodoro_walks_a_full_cycle() {
+        let clock = FakeClock::default();
+        let mut scheduler = pomodoro(&clock);
+        assert_eq!(
+            phase(&clock, &scheduler),
+            (PomodoroPhase::Work, 25 * MINUTE)
+        );
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 25 * MINUTE));
+
+        clock.advance(25 * MINUTE);
+        let events = scheduler.poll();
+        assert_eq!(durations(&events), [300]);
+        assert_eq!(kinds(events), [SHORT_BREAK]);
+        assert_eq!(
+            phase(&clock, &scheduler),
+            (PomodoroPhase::ShortBreak, 5 * MINUTE)
+        );
+        assert_eq!(scheduler.next_deadline(), Some(clock.now() + 5 * MINUTE));
+
+        clock.advance(5 * MINUTE);
+        assert!(scheduler.poll().is_empty());
+        assert_eq!(
+            phase(&clock, &scheduler),
+            (PomodoroPhase::Work, 25 * MINUTE)
+        );
+
+        // The second session of the cycle ends in a long break
+        clock.advance(25 * MINUTE);
+        let events = scheduler.poll();
+        assert_eq!(durations(&events), [900]);
+        assert_eq!(kinds(events), [LONG_BREAK]);
+        assert_eq!(
+            phase(&clock, &scheduler),
+            (PomodoroPhase::LongBreak, 15 * MINUTE)
+        );
+
+        // ...after which a new cycle starts
+        clock.advance(15 * MINUTE);
+        assert!(scheduler.poll().is_empty());
+        assert_eq!(
+            phase(&clock, &scheduler),
+            (PomodoroPhase::Work, 25 * MINUTE)
+        );
+        clock.advance(25 * MINUTE);
+        assert_eq!(kinds(scheduler.poll()), [SHORT_BREAK]);
+    }
+
+    #[test]
+    fn snoozed_pomodoro_break_comes_back_after_the_snooze_length() {
+        let clock = FakeClock::default();
+        let mut scheduler = pomodoro(&clock);
+        clock.advance(25 * MINUTE);
+        assert_eq!(kinds(scheduler.poll()), [SHORT_BREAK]);
+        scheduler.handle(SchedulerCommand::Respond {
+            kind: SHORT_BREAK,
+            outcome: ReminderOutcome::Snoozed,
+        });
+        assert_eq!(
+            phase(&clock, &scheduler),
+            (PomodoroPhase::Work, SNOOZE_LENGTH)
+        );
+        clock.advance(SNOOZE_LENGTH);
+        assert_eq!(kinds(scheduler.poll()), [SHORT_BREAK]);
+
+        // A postponed long break is still a long break
+        clock.advance(5 * MINUTE);
+        assert!(scheduler.poll().is_empty());
+        clock.advance(25 * MINUTE);
+        assert_eq!(kinds(scheduler.poll()), [LONG_BREAK]);
+        scheduler.handle(SchedulerCommand::Respond {
+            kind: LONG_BREAK,
+            outcome: ReminderOutcome::Snoozed,
+        });
+        clock.advance(SNOOZE_LENGTH);
+        assert_eq!(kinds(scheduler.poll()), [LONG_BREAK]);
+    }
+
+    #[test]
+    fn ending_a_pomodoro_break_early_starts_a_fresh_work_session() {
+        for outcome in [ReminderOutcome::Done, ReminderOutcome::Skipped] {
+            let clock = FakeClock::default();
+            let mut scheduler = pomodoro(&clock);
+            clock.advance(25 * MINUTE);
+            assert_eq!(kinds(scheduler.poll()), [SHORT_BREAK]);
+            clock.advance(MINUTE);
+            scheduler.handle(SchedulerCommand::Respond {
+                kind: SHORT_BREAK,
+                outcome,
+            });
+            assert_eq!(
+                phase(&clock, &scheduler),
+                (PomodoroPhase::Work, 25 * MINUTE)
+            );
+            // The other reminders count from the end of the break too
+            assert_eq!(scheduler.countdowns()[0].due, clock.now() + 36000 * SECOND);
+
+            // Answering again once back at work changes nothing
+            clock.advance(MINUTE);
+            scheduler.handle(SchedulerCommand::Respond {
+                kind: SHORT_BREAK,
+                outcome,
+            });
+            assert_eq!(
+                phase(&clock, &scheduler),
+                (PomodoroPhase::Work, 24 * MINUTE)
+            );
+        }
+    }
+}
@@ -1,14 +1,74 @@
+use crate::adaptive::{AdaptiveSettings, IntervalAdjustment};
+use crate::config;
+use crate::pomodoro::{PomodoroSettings, PomodoroStatus};
+use crate::reminder::{CustomReminder, DeliverySettings, ReminderDefinition, ReminderType};
+use crate::schedule::WeeklySchedule;
+use crate::scheduler::{ReminderEvent, SchedulerCommand};
 use once_cell::sync::Lazy;
-use std::sync::{Arc, RwLock};
-use tokio::sync::broadcast;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::{broadcast, watch};
 
 /// The shared application state for blink settings.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BlinkSettings {
     pub blink_interval: u64,
     pub blink_duration: u64,
+    /// Random deviation from `blink_interval`, in percent (0 disables).
+    pub blink_jitter: u8,
     pub posture_interval: u64,
     pub posture_duration: u64,
+    /// Random deviation from `posture_interval`, in percent (0 disables).
+    pub posture_jitter: u8,
+    pub eye_break_enabled: bool,
+    pub eye_break_interval: u64,
+    pub eye_break_duration: u64,
+    /// Random deviation from `eye_break_interval`, in percent (0 disables).
+    pub eye_break_jitter: u8,
+    /// User-defined reminders beyond blink, posture and eye breaks.
+    pub custom_reminders: Vec<CustomReminder>,
+    /// Id for the next custom reminder. Only ever grows, so a new reminder
+    /// never inherits the delivery options or history of a removed one.
+    pub next_custom_id: u32,
+    /// Seconds without input after which countdowns freeze (0 disables).
+    pub idle_threshold: u64,
+    /// Seconds of idle time that count as a completed posture break (0 disables).
+    pub idle_break_threshold: u64,
+    /// Whether an idle break also restarts the blink countdown.
+    pub idle_break_resets_blink: bool,
+    /// Hold back popups while a fullscreen app is in front.
+    pub defer_during_fullscreen: bool,
+    /// Seconds a popup may be held back by a fullscreen app (0 = until it ends).
+    pub max_fullscreen_deferral: u64,
+    /// Local `.ics` files whose busy events hold back popups.
+    pub calendar_files: Vec<PathBuf>,
+    /// Show a break as soon as a calendar meeting ends.
+    pub post_meeting_break: bool,
+    /// Seconds the post-meeting break lasts.
+    pub post_meeting_break_duration: u64,
+    /// Ignored or skipped reminders in a row before the popup gets bigger (0 = never).
+    /// Blink and posture popups closing on their own don't count.
+    pub escalate_after: u32,
+    /// Ignored or skipped reminders in a row before it goes full-screen (0 = never).
+    pub fullscreen_after: u32,
+    /// Days and times during which reminders may fire.
+    pub schedule: WeeklySchedule,
+    pub pomodoro: PomodoroSettings,
+    /// Lengthen blink and posture intervals that keep getting dismissed.
+    pub adaptive: AdaptiveSettings,
+    /// Interval changes adaptive mode made since the last reset, oldest first.
+    pub adaptive_log: Vec<IntervalAdjustment>,
+    /// Per-type delivery options, keyed by [`ReminderType::key`]. Types
+    /// without an entry use the defaults.
+    pub delivery: BTreeMap<String, DeliverySettings>,
+    /// Silence every sound cue without losing the per-type choices.
+    pub sound_muted: bool,
+    /// Days of reminder history to keep (0 = forever).
+    pub history_retention_days: u32,
 }
 
 impl Default for BlinkSettings {
@@ -16,15 +76,246 @@ impl Default for BlinkSettings {
         Self {
             blink_interval: 30,
             blink_duration: 3,
+            blink_jitter: 0,
             posture_interval: 60,
             posture_duration: 5,
+            posture_jitter: 0,
+            eye_break_enabled: false,
+            eye_break_interval: 20 * 60,
+            eye_break_duration: 20,
+            eye_break_jitter: 0,
+            custom_reminders: Vec::new(),
+            next_custom_id: 0,
+            idle_threshold: 120,
+            idle_break_threshold: 300,
+            idle_break_resets_blink: false,
+            defer_during_fullscreen: true,
+            max_fullscreen_deferral: 1800,
+            calendar_files: Vec::new(),
+            post_meeting_break: false,
+            post_meeting_break_duration: 120,
+            escalate_after: 3,
+            fullscreen_after: 6,
+            schedule: WeeklySchedule::default(),
+            pomodoro: PomodoroSettings::default(),
+            adaptive: AdaptiveSettings::default(),
+            adaptive_log: Vec::new(),
+            delivery: BTreeMap::new(),
+            sound_muted: false,
+            history_retention_days: 365,
+        }
+    }
+}
+
+impl BlinkSettings {
+    /// Every reminder with a countdown, built-in ones first.
+    pub fn reminder_definitions(&self) -> Vec<ReminderDefinition> {
+        let built_in = [
+            ReminderDefinition {
+                kind: ReminderType::Blink,
+                enabled: true,
+                interval: self.blink_interval,
+                duration: self.blink_duration,
+                jitter: self.blink_jitter,
+            },
+            ReminderDefinition {
+                kind: ReminderType::Posture,
+                enabled: true,
+                interval: self.posture_interval,
+                duration: self.posture_duration,
+                jitter: self.posture_jitter,
+            },
+            ReminderDefinition {
+                kind: ReminderType::EyeBreak,
+                enabled: self.eye_break_enabled,
+                interval: self.eye_break_interval,
+                duration: self.eye_break_duration,
+                jitter: self.eye_break_jitter,
+            },
+        ];
+        built_in
+            .into_iter()
+            .chain(self.custom_reminders.iter().map(CustomReminder::definition))
+            .collect()
+    }
+
+    /// The definition of `kind`, or `None` for breaks without a countdown
+    /// and for removed reminders.
+    pub fn reminder_definition(&self, kind: ReminderType) -> Option<ReminderDefinition> {
+        self.reminder_definitions()
+            .into_iter()
+            .find(|definition| definition.kind == kind)
+    }
+
+    /// Every reminder type these settings define, built-in ones first.
+    pub fn reminder_kinds(&self) -> Vec<ReminderType> {
+        self.reminder_definitions()
+            .into_iter()
+            .map(|definition| definition.kind)
+            .collect()
+    }
+
+    /// Every reminder type, including the breaks that only fire on their
+    /// own terms (after meetings, between Pomodoro sessions).
+    pub fn all_reminder_kinds(&self) -> Vec<ReminderType> {
+        let mut kinds = self.reminder_kinds();
+        kinds.push(ReminderType::MeetingBreak);
+        kinds.push(ReminderType::PomodoroBreak { long: false });
+        kinds
+    }
+
+    /// The name the user knows `kind` by.
+    pub fn reminder_name(&self, kind: ReminderType) -> String {
+        match kind {
+            ReminderType::Blink => "Blink".to_string(),
+            ReminderType::Posture => "Posture".to_string(),
+            ReminderType::EyeBreak => "Eye break".to_string(),
+            ReminderType::Custom(id) => self
+                .custom_reminders
+                .iter()
+                .find(|reminder| reminder.id == id)
+                .map_or_else(|| format!("Custom #{id}"), |reminder| reminder.name.clone()),
+            ReminderType::MeetingBreak => "Post-meeting break".to_string(),
+            ReminderType::PomodoroBreak { .. } => "Pomodoro break".to_string(),
+        }
+    }
+
+    /// Delivery options for `kind`.
+    pub fn delivery_for(&self, kind: ReminderType) -> DeliverySettings {
+        self.delivery.get(&kind.key()).cloned().unwrap_or_default()
+    }
+
+    /// Drop the delivery options of reminders that no longer exist.
+    pub fn forget_removed_reminders(&mut self) {
+        let keys: HashSet<String> = self
+            .all_reminder_kinds()
+            .into_iter()
+            .map(ReminderType::key)
+            .collect();
+        self.delivery.retain(|key, _| keys.contains(key));
+    }
+
+    /// Apply what changed from `before` to `after`, e.g. in the settings
+    /// window since it opened. Everything else keeps its current value, so
+    /// an interval adaptive mode changed or a popup dragged in the meantime
+    /// isn't reverted.
+    pub fn apply_edits(&mut self, before: &BlinkSettings, after: &BlinkSettings) {
+        macro_rules! apply_edited {
+            ($($field:ident),* $(,)?) => {
+                // Naming every field makes a new one a compile error until it is listed
+                let BlinkSettings { $($field: _,)* adaptive_log: _, delivery: _ } = after;
+                $(
+                    if after.$field != before.$field {
+                        self.$field = after.$field.clone();
+                    }
+                )*
+            };
+        }
+        apply_edited!(
+            blink_interval,
+            blink_duration,
+            blink_jitter,
+            posture_interval,
+            posture_duration,
+            posture_jitter,
+            eye_break_enabled,
+            eye_break_interval,
+            eye_break_duration,
+            eye_break_jitter,
+            custom_reminders,
+            next_custom_id,
+            idle_threshold,
+            idle_break_threshold,
+            idle_break_resets_blink,
+            defer_during_fullscreen,
+            max_fullscreen_deferral,
+            calendar_files,
+            post_meeting_break,
+            post_meeting_break_duration,
+            escalate_after,
+            fullscreen_after,
+            schedule,
+            pomodoro,
+            adaptive,
+            sound_muted,
+            history_retention_days,
+        );
+        // Only entries can be dropped from the log, by resetting it
+        self.adaptive_log.retain(|adjustment| {
+            !before.adaptive_log.contains(adjustment) || after.adaptive_log.contains(adjustment)
+        });
+        let keys: HashSet<&String> = before
+            .delivery
+            .keys()
+            .chain(after.delivery.keys())
+            .collect();
+        for key in keys {
+            let (was, now) = (before.delivery.get(key), after.delivery.get(key));
+            if was == now {
+                continue;
+            }
+            let Some(now) = now else {
+                self.delivery.remove(key);
+                continue;
+            };
+            let mut options = now.clone();
+            // Keep a position dragged to meanwhile, unless the edit cleared it
+            if now.dragged_position == was.and_then(|was| was.dragged_position) {
+                options.dragged_position = self
+                    .delivery
+                    .get(key)
+                    .and_then(|current| current.dragged_position);
+            }
+            self.delivery.insert(key.clone(), options);
+        }
+    }
+
+    /// Raise intervals of zero, which a hand-edited file may contain, to one
+    /// second.
+    pub fn clamp_intervals(&mut self) {
+        for interval in [
+            &mut self.blink_interval,
+            &mut self.posture_interval,
+            &mut self.eye_break_interval,
+        ]
+        .into_iter()
+        .chain(
+            self.custom_reminders
+                .iter_mut()
+                .map(|reminder| &mut reminder.interval),
+        ) {
+            *interval = (*interval).max(1);
         }
     }
 }
 
 /// A globally accessible, thread-safe shared state for blink settings.
-pub static SHARED_BLINK_SETTINGS: Lazy<Arc<RwLock<BlinkSettings>>> =
-    Lazy::new(|| Arc::new(RwLock::new(BlinkSettings::default())));
+/// Initialized from the config file on first access.
+pub static SHARED_BLINK_SETTINGS: Lazy<Arc<RwLock<BlinkSettings>>> = Lazy::new(|| {
+    let (settings, warning) = config::load_or_default();
+    *CONFIG_WARNING.write().unwrap() = warning;
+    Arc::new(RwLock::new(settings))
+});
+
+/// The last problem loading or saving the config file, shown in the settings window.
+pub static CONFIG_WARNING: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
+
+/// The scheduler's latest Pomodoro phase, shown in the tray.
+/// Watched so the tray only refreshes while a session runs.
+pub static POMODORO_STATUS: Lazy<watch::Sender<Option<PomodoroStatus>>> =
+    Lazy::new(|| watch::Sender::new(None));
+
+/// Reminders currently on screen, by showing id, with one entry per reminder
+/// even when it is shown on several monitors.
+static OPEN_SHOWINGS: Lazy<Mutex<HashSet<u64>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+static NEXT_SHOWING: AtomicU64 = AtomicU64::new(0);
+
+/// Broadcast channel for showings that were answered, so the windows showing
+/// the same reminder on other monitors close too.
+pub static ANSWERED_CHANNEL: Lazy<broadcast::Sender<u64>> = Lazy::new(|| {
+    let (tx, _) = broadcast::channel(16);
+    tx
+});
 
 /// Broadcast channel for settings changes (tokio broadcast)
 pub static SETTINGS_CHANNEL: Lazy<broadcast::Sender<BlinkSettings>> = Lazy::new(|| {
@@ -33,7 +324,24 @@ pub static SETTINGS_CHANNEL: Lazy<broadcast::Sender<BlinkSettings>> = Lazy::new(
     tx
 });
 
+/// Broadcast channel for reminders the scheduler has found due.
+pub static REMINDER_CHANNEL: Lazy<broadcast::Sender<ReminderEvent>> = Lazy::new(|| {
+    let (tx, _) = broadcast::channel(16);
+    tx
+});
+
+/// Broadcast channel for commands to the scheduler (pause, resume, ...).
+pub static COMMAND_CHANNEL: Lazy<broadcast::Sender<SchedulerCommand>> = Lazy::new(|| {
+    let (tx, _) = broadcast::channel(16);
+    tx
+});
+
 /// Helper functions for reading and writing the shared state.
+/// Get a snapshot of all settings.
+pub fn get_settings() -> BlinkSettings {
+    SHARED_BLINK_SETTINGS.read().unwrap().clone()
+}
+
 /// Get the current blink interval.
 pub fn get_blink_interval() -> u64 {
     SHARED_BLINK_SETTINGS.read().unwrap().blink_interval
@@ -44,26 +352,6 @@ pub fn get_posture_interval() -> u64 {
     SHARED_BLINK_SETTINGS.read().unwrap().posture_interval
 }
 
-/// Set the blink interval and broadcast the change.
-pub fn set_blink_interval(val: u64) {
-    {
-        let mut state = SHARED_BLINK_SETTINGS.write().unwrap();
-        state.blink_interval = val;
-        // Broadcast the new state
-        let _ = SETTINGS_CHANNEL.send(state.clone());
-    }
-}
-
-/// Set the posture interval and broadcast the change.
-pub fn set_posture_interval(val: u64) {
-    {
-        let mut state = SHARED_BLINK_SETTINGS.write().unwrap();
-        state.posture_interval = val;
-        // Broadcast the new state
-        let _ = SETTINGS_CHANNEL.send(state.clone());
-    }
-}
-
 /// Get the current blink duration.
 pub fn get_blink_duration() -> u64 {
     SHARED_BLINK_SETTINGS.read().unwrap().blink_duration
@@ -74,27 +362,387 @@ pub fn get_posture_duration() -> u64 {
     SHARED_BLINK_SETTINGS.read().unwrap().posture_duration
 }
 
-/// Set the blink duration and broadcast the change.
-pub fn set_blink_duration(val: u64) {
-    {
-        let mut state = SHARED_BLINK_SETTINGS.write().unwrap();
-        state.blink_duration = val;
-        // Broadcast the new state
-        let _ = SETTINGS_CHANNEL.send(state.clone());
-    }
+/// Get the blink jitter percentage.
+pub fn get_blink_jitter() -> u8 {
+    SHARED_BLINK_SETTINGS.read().unwrap().blink_jitter
+}
+
+/// Get the posture jitter percentage.
+pub fn get_posture_jitter() -> u8 {
+    SHARED_BLINK_SETTINGS.read().unwrap().posture_jitter
+}
+
+/// Get the eye-break jitter percentage.
+pub fn get_eye_break_jitter() -> u8 {
+    SHARED_BLINK_SETTINGS.read().unwrap().eye_break_jitter
+}
+
+/// Get whether eye-break reminders are enabled.
+pub fn get_eye_break_enabled() -> bool {
+    SHARED_BLINK_SETTINGS.read().unwrap().eye_break_enabled
+}
+
+/// Get the current eye-break interval.
+pub fn get_eye_break_interval() -> u64 {
+    SHARED_BLINK_SETTINGS.read().unwrap().eye_break_interval
+}
+
+/// Get the current eye-break duration.
+pub fn get_eye_break_duration() -> u64 {
+    SHARED_BLINK_SETTINGS.read().unwrap().eye_break_duration
+}
+
+/// Get all user-defined reminders.
+pub fn get_custom_reminders() -> Vec<CustomReminder> {
+    SHARED_BLINK_SETTINGS
+        .read()
+        .unwrap()
+        .custom_reminders
+        .clone()
+}
+
+/// Get the id the next custom reminder will get.
+pub fn get_next_custom_id() -> u32 {
+    SHARED_BLINK_SETTINGS.read().unwrap().next_custom_id
+}
+
+/// Get the user-defined reminder with the given id.
+pub fn get_custom_reminder(id: u32) -> Option<CustomReminder> {
+    SHARED_BLINK_SETTINGS
+        .read()
+        .unwrap()
+        .custom_reminders
+        .iter()
+        .find(|reminder| reminder.id == id)
+        .cloned()
+}
+
+/// Get the current idle threshold.
+pub fn get_idle_threshold() -> u64 {
+    SHARED_BLINK_SETTINGS.read().unwrap().idle_threshold
+}
+
+/// Get the current idle break threshold.
+pub fn get_idle_break_threshold() -> u64 {
+    SHARED_BLINK_SETTINGS.read().unwrap().idle_break_threshold
+}
+
+/// Get whether an idle break also restarts the blink countdown.
+pub fn get_idle_break_resets_blink() -> bool {
+    SHARED_BLINK_SETTINGS
+        .read()
+        .unwrap()
+        .idle_break_resets_blink
 }
 
-/// Set the posture duration and broadcast the change.
-pub fn set_posture_duration(val: u64) {
-    {
-        let mut state = SHARED_BLINK_SETTINGS.write().unwrap();
-        state.posture_duration = val;
-        // Broadcast the new state
-        let _ = SETTINGS_CHANNEL.send(state.clone());
+/// Get whether popups are held back while a fullscreen app is in front.
+pub fn get_defer_during_fullscreen() -> bool {
+    SHARED_BLINK_SETTINGS
+        .read()
+        .unwrap()
+        .defer_during_fullscreen
+}
+
+/// Get the longest a fullscreen app may hold back a popup, in seconds.
+pub fn get_max_fullscreen_deferral() -> u64 {
+    SHARED_BLINK_SETTINGS
+        .read()
+        .unwrap()
+        .max_fullscreen_deferral
+}
+
+/// Get the calendar files checked for meetings.
+pub fn get_calendar_files() -> Vec<PathBuf> {
+    SHARED_BLINK_SETTINGS.read().unwrap().calendar_files.clone()
+}
+
+/// Get whether a break is shown when a meeting ends.
+pub fn get_post_meeting_break() -> bool {
+    SHARED_BLINK_SETTINGS.read().unwrap().post_meeting_break
+}
+
+/// Get the post-meeting break length in seconds.
+pub fn get_post_meeting_break_duration() -> u64 {
+    SHARED_BLINK_SETTINGS
+        .read()
+        .unwrap()
+        .post_meeting_break_duration
+}
+
+/// Get how many ignored reminders in a row make the popup bigger.
+pub fn get_escalate_after() -> u32 {
+    SHARED_BLINK_SETTINGS.read().unwrap().escalate_after
+}
+
+/// Get how many ignored reminders in a row make the popup full-screen.
+pub fn get_fullscreen_after() -> u32 {
+    SHARED_BLINK_SETTINGS.read().unwrap().fullscreen_after
+}
+
+/// Get the current working-hours schedule.
+pub fn get_schedule() -> WeeklySchedule {
+    SHARED_BLINK_SETTINGS.read().unwrap().schedule.clone()
+}
+
+/// Get the current Pomodoro settings.
+pub fn get_pomodoro() -> PomodoroSettings {
+    SHARED_BLINK_SETTINGS.read().unwrap().pomodoro.clone()
+}
+
+/// Get the adaptive interval settings.
+pub fn get_adaptive() -> AdaptiveSettings {
+    SHARED_BLINK_SETTINGS.read().unwrap().adaptive.clone()
+}
+
+/// Get the per-type delivery options.
+pub fn get_delivery() -> BTreeMap<String, DeliverySettings> {
+    SHARED_BLINK_SETTINGS.read().unwrap().delivery.clone()
+}
+
+/// Get whether sound cues are muted.
+pub fn get_sound_muted() -> bool {
+    SHARED_BLINK_SETTINGS.read().unwrap().sound_muted
+}
+
+/// Get how many days of reminder history are kept.
+pub fn get_history_retention_days() -> u32 {
+    SHARED_BLINK_SETTINGS.read().unwrap().history_retention_days
+}
+
+/// Get the delivery options for one reminder type.
+pub fn get_delivery_for(kind: ReminderType) -> DeliverySettings {
+    SHARED_BLINK_SETTINGS.read().unwrap().delivery_for(kind)
+}
+
+/// Remember where the user dragged a `kind` popup, relative to its monitor.
+pub fn remember_position(kind: ReminderType, position: (f64, f64)) {
+    update_settings(|state| {
+        state
+            .delivery
+            .entry(kind.key())
+            .or_default()
+            .dragged_position = Some(position);
+    });
+}
+
+/// Get the interval changes adaptive mode made since the last reset.
+pub fn get_adaptive_log() -> Vec<IntervalAdjustment> {
+    SHARED_BLINK_SETTINGS.read().unwrap().adaptive_log.clone()
+}
+
+/// Apply an adaptive interval change and log it for the settings window.
+pub fn apply_adaptive_adjustment(adjustment: IntervalAdjustment) {
+    update_settings(|state| {
+        match adjustment.kind {
+            ReminderType::Blink => state.blink_interval = adjustment.to,
+            ReminderType::Posture => state.posture_interval = adjustment.to,
+            _ => return,
+        }
+        state.adaptive_log.push(adjustment);
+    });
+}
+
+/// Publish the current Pomodoro phase.
+pub fn set_pomodoro_status(val: Option<PomodoroStatus>) {
+    POMODORO_STATUS.send_if_modified(|status| {
+        let changed = *status != val;
+        *status = val;
+        changed
+    });
+}
+
+/// Get a receiver that is notified when the Pomodoro phase changes.
+pub fn pomodoro_status_receiver() -> watch::Receiver<Option<PomodoroStatus>> {
+    POMODORO_STATUS.subscribe()
+}
+
+/// Get the last config file warning, if any.
+pub fn config_warning() -> Option<String> {
+    CONFIG_WARNING.read().unwrap().clone()
+}
+
+/// Save the changes the settings window made from `before` to `after`,
+/// with a single save and broadcast. See [`BlinkSettings::apply_edits`].
+pub fn save_edits(before: &BlinkSettings, after: &BlinkSettings) {
+    update_settings(|state| {
+        state.apply_edits(before, after);
+        state.forget_removed_reminders();
+    });
+}
+
+/// Apply `f` to the settings, save them to disk and broadcast the new state.
+fn update_settings(f: impl FnOnce(&mut BlinkSettings)) {
+    let mut state = SHARED_BLINK_SETTINGS.write().unwrap();
+    f(&mut state);
+    match config::save(&state) {
+        Ok(()) => *CONFIG_WARNING.write().unwrap() = None,
+        Err(err) => {
+            eprintln!("Warning: {err}");
+            *CONFIG_WARNING.write().unwrap() = Some(err.to_string());
+        }
     }
+    // Broadcast the new state
+    let _ = SETTINGS_CHANNEL.send(state.clone());
 }
 
 /// Listen for settings changes (returns a new broadcast receiver)
 pub fn settings_receiver() -> broadcast::Receiver<BlinkSettings> {
     SETTINGS_CHANNEL.subscribe()
 }
+
+/// Listen for due reminders (returns a new broadcast receiver)
+pub fn reminder_receiver() -> broadcast::Receiver<ReminderEvent> {
+    REMINDER_CHANNEL.subscribe()
+}
+
+/// Send a command to the scheduler.
+pub fn send_command(command: SchedulerCommand) {
+    let _ = COMMAND_CHANNEL.send(command);
+}
+
+/// Listen for scheduler commands (returns a new broadcast receiver)
+pub fn command_receiver() -> broadcast::Receiver<SchedulerCommand> {
+    COMMAND_CHANNEL.subscribe()
+}
+
+/// Register a reminder about to be shown and return its showing id.
+pub fn open_showing() -> u64 {
+    let id = NEXT_SHOWING.fetch_add(1, Ordering::Relaxed);
+    OPEN_SHOWINGS.lock().unwrap().insert(id);
+    id
+}
+
+/// Claim the answer for a showing. Only the first of its windows to answer
+/// gets `true`; the others are closed through [`ANSWERED_CHANNEL`].
+pub fn answer_showing(id: u64) -> bool {
+    let first = OPEN_SHOWINGS.lock().unwrap().remove(&id);
+    if first {
+        let _ = ANSWERED_CHANNEL.send(id);
+    }
+    first
+}
+
+/// Subscribe to answered showings.
+pub fn answered_receiver() -> broadcast::Receiver<u64> {
+    ANSWERED_CHANNEL.subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_reminders_lose_their_delivery_options() {
+        let mut settings = BlinkSettings {
+            custom_reminders: vec![CustomReminder {
+                id: 1,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        for kind in [
+            ReminderType::Blink,
+            ReminderType::Custom(0),
+            ReminderType::Custom(1),
+            ReminderType::PomodoroBreak { long: true },
+        ] {
+            settings
+                .delivery
+                .insert(kind.key(), DeliverySettings::default());
+        }
+        settings.forget_removed_reminders();
+        let keys: Vec<&str> = settings.delivery.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["blink", "custom_1", "pomodoro_break"]);
+    }
+
+    #[test]
+    fn edits_leave_changes_made_meanwhile_alone() {
+        let before = BlinkSettings::default();
+        // Adaptive mode and a dragged popup changed things while the window was open
+        let mut current = BlinkSettings {
+            blink_interval: 99,
+            ..before.clone()
+        };
+        current.delivery.insert(
+            ReminderType::Blink.key(),
+            DeliverySettings {
+                dragged_position: Some((10.0, 20.0)),
+                ..Default::default()
+            },
+        );
+        let after = BlinkSettings {
+            posture_interval: 1234,
+            ..before.clone()
+        };
+        current.apply_edits(&before, &after);
+        assert_eq!(current.blink_interval, 99);
+        assert_eq!(current.posture_interval, 1234);
+        assert_eq!(
+            current.delivery_for(ReminderType::Blink).dragged_position,
+            Some((10.0, 20.0))
+        );
+    }
+
+    #[test]
+    fn delivery_edits_keep_a_position_dragged_meanwhile() {
+        let before = BlinkSettings::default();
+        let dragged = |position| DeliverySettings {
+            dragged_position: position,
+            ..Default::default()
+        };
+        let mut current = before.clone();
+        current
+            .delivery
+            .insert(ReminderType::Blink.key(), dragged(Some((10.0, 20.0))));
+        let mut after = before.clone();
+        after.delivery.insert(
+            ReminderType::Blink.key(),
+            DeliverySettings {
+                opacity: 0.5,
+                ..Default::default()
+            },
+        );
+        current.apply_edits(&before, &after);
+        let blink = current.delivery_for(ReminderType::Blink);
+        assert_eq!(blink.opacity, 0.5);
+        assert_eq!(blink.dragged_position, Some((10.0, 20.0)));
+
+        // ...unless the edit was to forget the dragged position
+        let before = current.clone();
+        let mut after = current.clone();
+        after
+            .delivery
+            .insert(ReminderType::Blink.key(), dragged(None));
+        current.apply_edits(&before, &after);
+        assert_eq!(
+            current.delivery_for(ReminderType::Blink).dragged_position,
+            None
+        );
+    }
+
+    #[test]
+    fn resetting_the_adaptive_log_keeps_adjustments_made_meanwhile() {
+        let adjustment = |from, to| IntervalAdjustment {
+            kind: ReminderType::Blink,
+            at: chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+            from,
+            to,
+            reason: "test".to_string(),
+        };
+        let before = BlinkSettings {
+            adaptive_log: vec![adjustment(30, 40)],
+            ..Default::default()
+        };
+        let mut current = before.clone();
+        current.adaptive_log.push(adjustment(40, 50));
+        let after = BlinkSettings {
+            adaptive_log: Vec::new(),
+            ..before.clone()
+        };
+        current.apply_edits(&before, &after);
+        assert_eq!(current.adaptive_log, vec![adjustment(40, 50)]);
+    }
+}
@@ -0,0 +1,477 @@
+use crate::components::reminder_window::{reminder_window, ReminderWindowProps};
+use crate::placement::{self, MonitorChoice, Rect};
+use crate::reminder::{EscalationLevel, ReminderOutcome, ReminderType};
+use crate::scheduler::SchedulerCommand;
+use crate::shared_state::{
+    get_custom_reminder, get_settings, open_showing, send_command, BlinkSettings,
+};
+use dioxus::desktop::tao::dpi::PhysicalPosition;
+use dioxus::desktop::tao::monitor::MonitorHandle;
+use dioxus::desktop::tao::window::Fullscreen;
+use dioxus::desktop::{window, Config, LogicalSize, WindowBuilder};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How a reminder type reaches the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkKind {
+    /// Blinkion's own always-on-top window.
+    #[default]
+    Overlay,
+    /// A native desktop notification with Done, Snooze and Skip buttons.
+    Notification,
+    /// A line on standard output, e.g. for a status bar script.
+    Log,
+}
+
+impl SinkKind {
+    pub const ALL: [SinkKind; 3] = [SinkKind::Overlay, SinkKind::Notification, SinkKind::Log];
+
+    /// Name shown in the settings window.
+    pub fn label(self) -> &'static str {
+        match self {
+            SinkKind::Overlay => "Window",
+            SinkKind::Notification => "Notification",
+            SinkKind::Log => "Log only",
+        }
+    }
+}
+
+/// Shows a due reminder to the user. How the user answers is sent back to
+/// the scheduler as a [`SchedulerCommand::Respond`].
+pub trait ReminderSink {
+    /// Present a `kind` reminder meant to stay up for `duration` seconds.
+    fn deliver(&self, kind: ReminderType, duration: u64, level: EscalationLevel);
+}
+
+/// Delivers reminders in a Dioxus window. Must be used from the UI thread.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OverlaySink;
+
+impl ReminderSink for OverlaySink {
+    fn deliver(&self, kind: ReminderType, duration: u64, level: EscalationLevel) {
+        open_reminder_window(kind, Some(duration), level);
+    }
+}
+
+/// Prints reminders to standard output. Nobody can answer them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogSink;
+
+impl ReminderSink for LogSink {
+    fn deliver(&self, kind: ReminderType, duration: u64, level: EscalationLevel) {
+        let (summary, body) = reminder_text(kind, duration);
+        println!(
+            "[{}] {summary}: {body} ({level:?})",
+            chrono::Local::now().format("%H:%M:%S")
+        );
+    }
+}
+
+/// Delivers reminders as `org.freedesktop.Notifications` toasts.
+///
+/// A background thread watches for the buttons being pressed and for the
+/// toast closing, and routes the answer to the scheduler.
+#[cfg(target_os = "linux")]
+pub struct NotificationSink {
+    proxy: zbus::blocking::Proxy<'static>,
+    /// Notifications on screen and what they remind of.
+    pending: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<u32, ReminderType>>>,
+}
+
+#[cfg(target_os = "linux")]
+impl NotificationSink {
+    /// Connect to the session bus. Returns `None` if there is no bus or no
+    /// notification server on it.
+    pub fn connect() -> Option<Self> {
+        use zbus::message::Type;
+        use zbus::MatchRule;
+
+        let conn = zbus::blocking::Connection::session().ok()?;
+        let proxy = zbus::blocking::Proxy::new(
+            &conn,
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+        )
+        .ok()?;
+        // Make sure a server is there before sending reminders to it
+        proxy
+            .call::<_, _, (String, String, String, String)>("GetServerInformation", &())
+            .ok()?;
+        let rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .interface("org.freedesktop.Notifications")
+            .ok()?
+            .path("/org/freedesktop/Notifications")
+            .ok()?
+            .build();
+        let signals = zbus::blocking::MessageIterator::for_match_rule(rule, &conn, None).ok()?;
+        let pending = std::sync::Arc::default();
+        let watched = std::sync::Arc::clone(&pending);
+        std::thread::spawn(move || route_answers(signals, watched));
+        Some(Self { proxy, pending })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl ReminderSink for NotificationSink {
+    /// Sends the toast from a task, as the call waits for the notification
+    /// server to answer and must not hold up the UI thread.
+    fn deliver(&self, kind: ReminderType, duration: u64, level: EscalationLevel) {
+        use std::collections::HashMap;
+        use zbus::zvariant::Value;
+
+        let (summary, body) = reminder_text(kind, duration);
+        // Escalated reminders are marked critical so they aren't hidden
+        let urgency: u8 = if level == EscalationLevel::Subtle {
+            1
+        } else {
+            2
+        };
+        let timeout = i32::try_from(duration * level.duration_factor() * 1000).unwrap_or(i32::MAX);
+        let proxy = self.proxy.inner().clone();
+        let pending = std::sync::Arc::clone(&self.pending);
+        spawn(async move {
+            let actions = ["done", "Done", "snooze", "Snooze 5 min", "skip", "Skip"];
+            let hints = HashMap::from([("urgency", Value::from(urgency))]);
+            let sent = proxy
+                .call::<_, _, u32>(
+                    "Notify",
+                    &(
+                        "Blinkion",
+                        0u32,
+                        "",
+                        summary.as_str(),
+                        body.as_str(),
+                        &actions[..],
+                        hints,
+                        timeout,
+                    ),
+                )
+                .await;
+            match sent {
+                Ok(id) => {
+                    pending.lock().unwrap().insert(id, kind);
+                }
+                Err(err) => {
+                    // Don't lose the reminder if the server went away
+                    eprintln!("Warning: could not send notification: {err}");
+                    OverlaySink.deliver(kind, duration, level);
+                }
+            }
+        });
+    }
+}
+
+/// Turn notification signals into scheduler answers until the bus goes away.
+#[cfg(target_os = "linux")]
+fn route_answers(
+    signals: zbus::blocking::MessageIterator,
+    pending: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<u32, ReminderType>>>,
+) {
+    for message in signals.flatten() {
+        let header = message.header();
+        let answer = match header.member().map(|member| member.as_str()) {
+            Some("ActionInvoked") => {
+                message
+                    .body()
+                    .deserialize::<(u32, String)>()
+                    .ok()
+                    .map(|(id, action)| {
+                        let outcome = match action.as_str() {
+                            "done" | "default" => ReminderOutcome::Done,
+                            "snooze" => ReminderOutcome::Snoozed,
+                            _ => ReminderOutcome::Skipped,
+                        };
+                        (id, outcome)
+                    })
+            }
+            // Reason 1 is "expired", 2 is "dismissed by the user". A toast
+            // that was answered is already gone from `pending`.
+            Some("NotificationClosed") => {
+                message
+                    .body()
+                    .deserialize::<(u32, u32)>()
+                    .ok()
+                    .map(|(id, reason)| {
+                        let outcome = if reason == 2 {
+                            ReminderOutcome::Skipped
+                        } else {
+                            ReminderOutcome::Ignored
+                        };
+                        (id, outcome)
+                    })
+            }
+            _ => None,
+        };
+        if let Some((id, outcome)) = answer {
+            if let Some(kind) = pending.lock().unwrap().remove(&id) {
+                send_command(SchedulerCommand::Respond { kind, outcome });
+            }
+        }
+    }
+}
+
+/// A sink that remembers what it was asked to deliver.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct RecordingSink {
+    delivered: std::rc::Rc<std::cell::RefCell<Vec<(ReminderType, u64, EscalationLevel)>>>,
+}
+
+#[cfg(test)]
+impl RecordingSink {
+    pub fn delivered(&self) -> Vec<(ReminderType, u64, EscalationLevel)> {
+        self.delivered.borrow().clone()
+    }
+}
+
+#[cfg(test)]
+impl ReminderSink for RecordingSink {
+    fn deliver(&self, kind: ReminderType, duration: u64, level: EscalationLevel) {
+        self.delivered.borrow_mut().push((kind, duration, level));
+    }
+}
+
+/// One sink of each kind, for picking per reminder type.
+pub struct ReminderSinks {
+    overlay: Box<dyn ReminderSink>,
+    notification: Option<Box<dyn ReminderSink>>,
+    log: Box<dyn ReminderSink>,
+}
+
+impl ReminderSinks {
+    /// Route to the given sinks. Without a notification sink, notifications
+    /// fall back to the overlay.
+    pub fn new(
+        overlay: Box<dyn ReminderSink>,
+        notification: Option<Box<dyn ReminderSink>>,
+        log: Box<dyn ReminderSink>,
+    ) -> Self {
+        Self {
+            overlay,
+            notification,
+            log,
+        }
+    }
+
+    /// The sinks available on this machine. Without a notification server,
+    /// notifications fall back to the overlay window.
+    pub async fn system() -> Self {
+        // Looking for the server waits on the session bus
+        #[cfg(target_os = "linux")]
+        let notification = tokio::task::spawn_blocking(NotificationSink::connect)
+            .await
+            .ok()
+            .flatten()
+            .map(|sink| Box::new(sink) as Box<dyn ReminderSink>);
+        #[cfg(not(target_os = "linux"))]
+        let notification = None;
+        Self::new(Box::new(OverlaySink), notification, Box::new(LogSink))
+    }
+
+    pub fn get(&self, kind: SinkKind) -> &dyn ReminderSink {
+        match kind {
+            SinkKind::Overlay => self.overlay.as_ref(),
+            SinkKind::Notification => self.notification.as_deref().unwrap_or(&*self.overlay),
+            SinkKind::Log => self.log.as_ref(),
+        }
+    }
+
+    /// Deliver a `kind` reminder through the sink `settings` choose for it.
+    pub fn deliver(
+        &self,
+        settings: &BlinkSettings,
+        kind: ReminderType,
+        duration: u64,
+        level: EscalationLevel,
+    ) {
+        self.get(settings.delivery_for(kind).sink)
+            .deliver(kind, duration, level);
+    }
+}
+
+/// Title and text for reminders that can't show Blinkion's own content.
+fn reminder_text(kind: ReminderType, duration: u64) -> (String, String) {
+    let settings = get_settings();
+    let body = match kind {
+        ReminderType::Blink => "Blink a few times and let your eyes rest.".to_string(),
+        ReminderType::Posture => "Sit up straight and relax your shoulders.".to_string(),
+        ReminderType::EyeBreak => {
+            format!("Look at something at least 20 feet (6 m) away for {duration} seconds.")
+        }
+        ReminderType::Custom(id) => get_custom_reminder(id)
+            .map(|reminder| reminder.message)
+            .unwrap_or_default(),
+        ReminderType::MeetingBreak => {
+            "Stand up, stretch and rest your eyes before getting back to work.".to_string()
+        }
+        ReminderType::PomodoroBreak { .. } => format!(
+            "Step away from the screen for {} minutes.",
+            duration.div_ceil(60)
+        ),
+    };
+    (settings.reminder_name(kind), body)
+}
+
+/// Open a transparent, always-on-top reminder window for `kind`, sized for
+/// its escalation `level`.
+fn open_reminder_window(kind: ReminderType, duration: Option<u64>, level: EscalationLevel) {
+    let title = match kind {
+        ReminderType::Blink => "Blinkion".to_string(),
+        ReminderType::Posture => "Posture Reminder".to_string(),
+        ReminderType::EyeBreak => "Eye Break".to_string(),
+        ReminderType::MeetingBreak => "Break".to_string(),
+        ReminderType::PomodoroBreak { long } => {
+            if long { "Long Break" } else { "Short Break" }.to_string()
+        }
+        ReminderType::Custom(id) => get_custom_reminder(id)
+            .map(|reminder| reminder.name)
+            .unwrap_or_else(|| "Reminder".to_string()),
+    };
+    let options = get_settings().delivery_for(kind);
+    let scale = level.scale() * options.scale;
+    let (width, height) = placement::base_size(kind);
+    let size = LogicalSize::new(width * scale, height * scale);
+    let showing = open_showing();
+    let cursor = window()
+        .cursor_position()
+        .ok()
+        .map(|cursor| (cursor.x, cursor.y));
+    let monitors = target_monitors(options.monitor, cursor);
+    // Without a known monitor, leave placement to the window manager
+    let monitors = if monitors.is_empty() {
+        vec![None]
+    } else {
+        monitors.into_iter().map(Some).collect()
+    };
+    for monitor in monitors {
+        let mut builder = WindowBuilder::new()
+            .with_title(title.clone())
+            .with_transparent(true)
+            .with_always_on_top(true)
+            .with_decorations(false)
+            .with_inner_size(size);
+        if let Some(monitor) = &monitor {
+            let area = Rect {
+                x: monitor.position().x.into(),
+                y: monitor.position().y.into(),
+                width: monitor.size().width.into(),
+                height: monitor.size().height.into(),
+            };
+            let physical = size.to_physical::<f64>(monitor.scale_factor());
+            let (x, y) = placement::window_position(
+                area,
+                physical.width,
+                physical.height,
+                monitor.scale_factor(),
+                &options,
+                cursor,
+            );
+            builder = builder.with_position(PhysicalPosition::new(x, y));
+        }
+        if level == EscalationLevel::Fullscreen {
+            builder = builder.with_fullscreen(Some(Fullscreen::Borderless(monitor)));
+        }
+        window().new_window(
+            VirtualDom::new_with_props(
+                reminder_window,
+                ReminderWindowProps {
+                    kind,
+                    duration,
+                    level,
+                    showing,
+                },
+            ),
+            Config::default().with_window(builder),
+        );
+    }
+}
+
+/// The monitors a reminder should appear on, or none if they can't be told apart.
+fn target_monitors(choice: MonitorChoice, cursor: Option<(f64, f64)>) -> Vec<MonitorHandle> {
+    let desktop = window();
+    let primary = || {
+        desktop
+            .primary_monitor()
+            .or_else(|| desktop.available_monitors().next())
+    };
+    match choice {
+        MonitorChoice::Cursor => cursor
+            .and_then(|(x, y)| desktop.monitor_from_point(x, y))
+            .or_else(primary)
+            .into_iter()
+            .collect(),
+        MonitorChoice::Primary => primary().into_iter().collect(),
+        MonitorChoice::All => desktop.available_monitors().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reminder::DeliverySettings;
+
+    fn sinks(notification: bool) -> (ReminderSinks, [RecordingSink; 3]) {
+        let recorders = [
+            RecordingSink::default(),
+            RecordingSink::default(),
+            RecordingSink::default(),
+        ];
+        let [overlay, notify, log] = recorders.clone();
+        let sinks = ReminderSinks::new(
+            Box::new(overlay),
+            notification.then(|| Box::new(notify) as Box<dyn ReminderSink>),
+            Box::new(log),
+        );
+        (sinks, recorders)
+    }
+
+    fn settings() -> BlinkSettings {
+        let mut settings = BlinkSettings::default();
+        for (kind, sink) in [
+            (ReminderType::Posture, SinkKind::Notification),
+            (ReminderType::EyeBreak, SinkKind::Log),
+        ] {
+            settings.delivery.insert(
+                kind.key(),
+                DeliverySettings {
+                    sink,
+                    ..Default::default()
+                },
+            );
+        }
+        settings
+    }
+
+    #[test]
+    fn each_type_goes_to_its_sink() {
+        let (sinks, [overlay, notification, log]) = sinks(true);
+        let settings = settings();
+        for kind in ReminderType::BUILT_IN {
+            sinks.deliver(&settings, kind, 5, EscalationLevel::Subtle);
+        }
+        let subtle = |kind| (kind, 5, EscalationLevel::Subtle);
+        assert_eq!(overlay.delivered(), [subtle(ReminderType::Blink)]);
+        assert_eq!(notification.delivered(), [subtle(ReminderType::Posture)]);
+        assert_eq!(log.delivered(), [subtle(ReminderType::EyeBreak)]);
+    }
+
+    #[test]
+    fn notifications_fall_back_to_the_overlay() {
+        let (sinks, [overlay, _, log]) = sinks(false);
+        sinks.deliver(
+            &settings(),
+            ReminderType::Posture,
+            5,
+            EscalationLevel::Prominent,
+        );
+        assert_eq!(
+            overlay.delivered(),
+            [(ReminderType::Posture, 5, EscalationLevel::Prominent)]
+        );
+        assert!(log.delivered().is_empty());
+    }
+}
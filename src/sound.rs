@@ -0,0 +1,222 @@
+use once_cell::sync::Lazy;
+use rodio::source::SineWave;
+use rodio::{Decoder, Source};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A sound played when a reminder appears.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundCue {
+    #[default]
+    Off,
+    /// Two rising notes.
+    Chime,
+    /// One long, fading note.
+    Bell,
+    /// A short click.
+    Pop,
+    /// A WAV or OGG file.
+    File(PathBuf),
+}
+
+impl SoundCue {
+    /// The cues that need no file, in the order the settings window lists them.
+    pub const BUILT_IN: [SoundCue; 4] = [
+        SoundCue::Off,
+        SoundCue::Chime,
+        SoundCue::Bell,
+        SoundCue::Pop,
+    ];
+
+    /// Name shown in the settings window.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SoundCue::Off => "None",
+            SoundCue::Chime => "Chime",
+            SoundCue::Bell => "Bell",
+            SoundCue::Pop => "Pop",
+            SoundCue::File(_) => "Audio file…",
+        }
+    }
+
+    /// Notes making up a built-in cue, as frequency in Hz and length in ms.
+    fn notes(&self) -> &'static [(f32, u64)] {
+        match self {
+            SoundCue::Chime => &[(660.0, 120), (880.0, 250)],
+            SoundCue::Bell => &[(523.0, 700)],
+            SoundCue::Pop => &[(1200.0, 60)],
+            SoundCue::Off | SoundCue::File(_) => &[],
+        }
+    }
+}
+
+/// Requests for the audio thread, which owns the output device.
+static PLAYER: Lazy<Mutex<mpsc::Sender<(SoundCue, f32)>>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || play_requests(rx));
+    Mutex::new(tx)
+});
+
+/// Play `cue` at `volume` (0 to 1) without blocking. Nothing happens if
+/// there is no audio output or the file can't be decoded.
+pub fn play(cue: &SoundCue, volume: f32) {
+    if *cue == SoundCue::Off {
+        return;
+    }
+    let _ = PLAYER.lock().unwrap().send((cue.clone(), volume));
+}
+
+/// Play every request on the default output device. The device is opened
+/// on first use and again after a failure, e.g. once headphones are plugged in.
+fn play_requests(requests: mpsc::Receiver<(SoundCue, f32)>) {
+    use rodio::{OutputStream, OutputStreamBuilder, Sink};
+
+    let mut stream: Option<OutputStream> = None;
+    for (cue, volume) in requests {
+        if stream.is_none() {
+            match OutputStreamBuilder::open_default_stream() {
+                Ok(mut opened) => {
+                    opened.log_on_drop(false);
+                    stream = Some(opened);
+                }
+                Err(err) => {
+                    eprintln!("Warning: no audio output, skipping sound cue: {err}");
+                    continue;
+                }
+            }
+        }
+        let Some(output) = &stream else {
+            continue;
+        };
+        let sink = Sink::connect_new(output.mixer());
+        sink.set_volume(volume.clamp(0.0, 1.0));
+        match cue_sources(&cue) {
+            Ok(sources) => sources.into_iter().for_each(|source| sink.append(source)),
+            Err(err) => eprintln!("Warning: cannot play {err}"),
+        }
+        // Keep playing after this handle goes away
+        sink.detach();
+    }
+}
+
+/// The sounds making up `cue`, played one after the other.
+fn cue_sources(cue: &SoundCue) -> Result<Vec<Box<dyn Source + Send>>, String> {
+    match cue {
+        SoundCue::File(path) => {
+            let decoded = std::fs::File::open(path)
+                .map_err(|err| err.to_string())
+                .and_then(|file| Decoder::try_from(file).map_err(|err| err.to_string()));
+            match decoded {
+                Ok(source) => Ok(vec![Box::new(source)]),
+                Err(err) => Err(format!("{}: {err}", path.display())),
+            }
+        }
+        cue => Ok(cue
+            .notes()
+            .iter()
+            .map(|&(frequency, millis)| {
+                let length = Duration::from_millis(millis);
+                Box::new(
+                    SineWave::new(frequency)
+                        .take_duration(length)
+                        .fade_out(length)
+                        .amplify(0.3),
+                ) as Box<dyn Source + Send>
+            })
+            .collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// How long `sources` play for, counted in samples.
+    fn length(sources: Vec<Box<dyn Source + Send>>) -> Duration {
+        sources
+            .into_iter()
+            .map(|source| {
+                let per_second = u64::from(source.sample_rate()) * u64::from(source.channels());
+                Duration::from_micros(source.count() as u64 * 1_000_000 / per_second)
+            })
+            .sum()
+    }
+
+    /// A mono 16-bit WAV file of `samples` silent samples at 8 kHz.
+    fn wav(samples: u32) -> Vec<u8> {
+        let data = samples * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&8000u32.to_le_bytes());
+        bytes.extend_from_slice(&16000u32.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data.to_le_bytes());
+        bytes.resize(bytes.len() + data as usize, 0);
+        bytes
+    }
+
+    #[test]
+    fn built_in_cues_play_their_notes() {
+        assert!(cue_sources(&SoundCue::Off).unwrap().is_empty());
+        let chime = cue_sources(&SoundCue::Chime).unwrap();
+        assert_eq!(chime.len(), 2);
+        assert_eq!(length(chime), Duration::from_millis(370));
+        assert_eq!(
+            length(cue_sources(&SoundCue::Bell).unwrap()),
+            Duration::from_millis(700)
+        );
+        assert_eq!(
+            length(cue_sources(&SoundCue::Pop).unwrap()),
+            Duration::from_millis(60)
+        );
+    }
+
+    #[test]
+    fn audio_files_are_decoded_and_unreadable_ones_reported() {
+        let dir = std::env::temp_dir().join(format!("blinkion-sound-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("cue.wav");
+        fs::write(&path, wav(4000)).unwrap();
+        let sources = cue_sources(&SoundCue::File(path)).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].sample_rate(), 8000);
+        assert_eq!(sources[0].channels(), 1);
+
+        let path = dir.join("notes.txt");
+        fs::write(&path, "not audio").unwrap();
+        let err = cue_sources(&SoundCue::File(path)).err().unwrap();
+        assert!(err.starts_with(&dir.join("notes.txt").display().to_string()));
+
+        let missing = dir.join("missing.ogg");
+        assert!(cue_sources(&SoundCue::File(missing)).is_err());
+    }
+
+    #[test]
+    fn cues_are_stored_by_name_or_path() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Options {
+            sound: SoundCue,
+        }
+        for sound in [SoundCue::Chime, SoundCue::File("/tmp/ding.ogg".into())] {
+            let options = Options { sound };
+            let text = toml::to_string(&options).unwrap();
+            assert_eq!(toml::from_str::<Options>(&text).unwrap(), options);
+        }
+        let options: Options = toml::from_str("sound = \"bell\"").unwrap();
+        assert_eq!(options.sound, SoundCue::Bell);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::history::{HistoryEntry, LifecycleEvent};
+use crate::reminder::ReminderType;
+use chrono::{Datelike, NaiveDate, TimeDelta, Timelike};
+use std::collections::BTreeMap;
+
+/// Share of its reminders a day must see completed to extend a streak.
+pub const STREAK_COMPLIANCE: f64 = 0.5;
+
+/// Reminders shown and completed over some stretch of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tally {
+    pub shown: u32,
+    /// Answered with "Done", or a break countdown that ran out.
+    pub completed: u32,
+}
+
+impl Tally {
+    fn add(&mut self, event: LifecycleEvent) {
+        match event {
+            LifecycleEvent::Shown => self.shown += 1,
+            LifecycleEvent::Acknowledged => self.completed += 1,
+            _ => {}
+        }
+    }
+
+    /// Completed reminders as a share of those shown, or `None` if nothing
+    /// was shown.
+    pub fn compliance(self) -> Option<f64> {
+        (self.shown > 0).then(|| f64::from(self.completed.min(self.shown)) / f64::from(self.shown))
+    }
+
+    /// Compliance as a whole percentage, e.g. "80%", or "–" if nothing was shown.
+    pub fn percent(self) -> String {
+        self.compliance()
+            .map_or_else(|| "–".to_string(), |share| format!("{:.0}%", share * 100.0))
+    }
+}
+
+/// A day, or the days of a week that fall in the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub start: NaiveDate,
+    /// The last day, included.
+    pub end: NaiveDate,
+    pub tally: Tally,
+}
+
+impl Period {
+    /// Whether this is a week from Monday to Sunday, rather than the part of
+    /// one at either end of the range.
+    pub fn is_whole_week(&self) -> bool {
+        self.end - self.start == TimeDelta::days(6)
+    }
+}
+
+/// Statistics for one reminder type. Short and long Pomodoro breaks are
+/// counted together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeStats {
+    pub kind: ReminderType,
+    pub total: Tally,
+    /// Days in a row, up to the last day this type was shown, on which at
+    /// least [`STREAK_COMPLIANCE`] of it was completed. Days it wasn't shown
+    /// at all are skipped rather than breaking the streak.
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// Mean seconds a completed reminder was on screen, or `None` if none
+    /// was completed.
+    pub average_break: Option<u64>,
+    /// By local hour of day, midnight first.
+    pub hours: [Tally; 24],
+}
+
+/// Compliance figures derived from the reminder history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    /// Every day of the range, including empty ones.
+    pub days: Vec<Period>,
+    /// Every week touching the range, starting on Mondays. The first and
+    /// last are cut short where the range starts or ends mid-week.
+    pub weeks: Vec<Period>,
+    pub total: Tally,
+    /// Like [`TypeStats::current_streak`], over all types together.
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// Ordered by [`ReminderType::key`].
+    pub types: Vec<TypeStats>,
+}
+
+/// Per-type totals while walking the history.
+#[derive(Default)]
+struct TypeAccumulator {
+    days: BTreeMap<NaiveDate, Tally>,
+    hours: [Tally; 24],
+    break_seconds: u64,
+}
+
+impl Statistics {
+    /// Tally `entries` falling on the days `from` to `to`, both included.
+    pub fn compute(entries: &[HistoryEntry], from: NaiveDate, to: NaiveDate) -> Self {
+        let mut days: BTreeMap<NaiveDate, Tally> = from
+            .iter_days()
+            .take_while(|day| *day <= to)
+            .map(|day| (day, Tally::default()))
+            .collect();
+        let mut types: BTreeMap<String, (ReminderType, TypeAccumulator)> = BTreeMap::new();
+        for entry in entries {
+            let day = entry.at.date_naive();
+            let Some(tally) = days.get_mut(&day) else {
+                continue;
+            };
+            tally.add(entry.event);
+            let (_, acc) = types
+                .entry(entry.kind.key())
+                .or_insert_with(|| (entry.kind, TypeAccumulator::default()));
+            acc.days.entry(day).or_default().add(entry.event);
+            acc.hours[entry.at.hour() as usize].add(entry.event);
+            if entry.event == LifecycleEvent::Acknowledged {
+                acc.break_seconds += entry.duration;
+            }
+        }
+
+        let mut weeks: BTreeMap<NaiveDate, Period> = BTreeMap::new();
+        for (&day, tally) in &days {
+            let week = weeks.entry(week_start(day)).or_insert(Period {
+                start: day,
+                end: day,
+                tally: Tally::default(),
+            });
+            week.end = day;
+            week.tally.shown += tally.shown;
+            week.tally.completed += tally.completed;
+        }
+        let (current_streak, longest_streak) = streaks(days.values().copied());
+        let types = types
+            .into_values()
+            .map(|(kind, acc)| {
+                let (current_streak, longest_streak) = streaks(acc.days.values().copied());
+                let total = sum(acc.days.values().copied());
+                TypeStats {
+                    kind,
+                    total,
+                    current_streak,
+                    longest_streak,
+                    average_break: (total.completed > 0)
+                        .then(|| acc.break_seconds / u64::from(total.completed)),
+                    hours: acc.hours,
+                }
+            })
+            .collect();
+        let days: Vec<Period> = days
+            .into_iter()
+            .map(|(start, tally)| Period {
+                start,
+                end: start,
+                tally,
+            })
+            .collect();
+        Self {
+            total: sum(days.iter().map(|day| day.tally)),
+            days,
+            weeks: weeks.into_values().collect(),
+            current_streak,
+            longest_streak,
+            types,
+        }
+    }
+}
+
+/// The Monday starting the week `day` falls in.
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - TimeDelta::days(day.weekday().num_days_from_monday().into())
+}
+
+fn sum(tallies: impl Iterator<Item = Tally>) -> Tally {
+    tallies.fold(Tally::default(), |total, tally| Tally {
+        shown: total.shown + tally.shown,
+        completed: total.completed + tally.completed,
+    })
+}
+
+/// Current and longest run of compliant days among `days`, oldest first.
+/// Days on which nothing was shown are skipped.
+fn streaks(days: impl Iterator<Item = Tally>) -> (u32, u32) {
+    let mut current = 0;
+    let mut longest = 0;
+    for compliance in days.filter_map(Tally::compliance) {
+        if compliance >= STREAK_COMPLIANCE {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn entry(day: u32, event: LifecycleEvent) -> HistoryEntry {
+        HistoryEntry {
+            at: Local.with_ymd_and_hms(2026, 10, day, 12, 0, 0).unwrap(),
+            kind: ReminderType::EyeBreak,
+            event,
+            duration: 20,
+        }
+    }
+
+    /// A day with `shown` reminders, `completed` of them done.
+    fn day(shown: u32, completed: u32) -> Tally {
+        Tally { shown, completed }
+    }
+
+    #[test]
+    fn days_without_reminders_dont_break_a_streak() {
+        let days = [day(2, 2), day(0, 0), day(4, 2), day(0, 0), day(1, 1)];
+        assert_eq!(streaks(days.into_iter()), (3, 3));
+    }
+
+    #[test]
+    fn a_non_compliant_day_resets_the_streak() {
+        // Half done is still compliant
+        let days = [day(2, 2), day(2, 1), day(2, 2), day(3, 1), day(1, 1)];
+        assert_eq!(streaks(days.into_iter()), (1, 3));
+        let days = [day(1, 1), day(1, 1), day(0, 0), day(4, 1)];
+        assert_eq!(streaks(days.into_iter()), (0, 2));
+    }
+
+    #[test]
+    fn weeks_start_on_monday_and_are_cut_at_the_range() {
+        use LifecycleEvent::*;
+        // Thursday the 8th to Tuesday the 20th
+        let entries = [
+            entry(7, Shown),
+            entry(8, Shown),
+            entry(8, Acknowledged),
+            entry(11, Shown),
+            entry(12, Shown),
+            entry(12, Acknowledged),
+            entry(20, Shown),
+            entry(21, Shown),
+        ];
+        let stats = Statistics::compute(&entries, date(8), date(20));
+        let weeks: Vec<(NaiveDate, NaiveDate, Tally, bool)> = stats
+            .weeks
+            .iter()
+            .map(|week| (week.start, week.end, week.tally, week.is_whole_week()))
+            .collect();
+        assert_eq!(
+            weeks,
+            [
+                (date(8), date(11), day(2, 1), false),
+                (date(12), date(18), day(1, 1), true),
+                (date(19), date(20), day(1, 0), false),
+            ]
+        );
+        assert_eq!(stats.days.len(), 13);
+        assert_eq!(stats.total, day(4, 2));
+    }
+}
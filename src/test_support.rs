@@ -0,0 +1,25 @@
+//! Fixtures shared by the scheduler tests in several modules.
+
+use crate::reminder::ReminderType;
+use crate::scheduler::ReminderEvent;
+use crate::shared_state::BlinkSettings;
+use std::time::Duration;
+
+pub const SECOND: Duration = Duration::from_secs(1);
+
+/// Blink every 30 s; posture far enough out not to interfere.
+pub fn settings() -> BlinkSettings {
+    BlinkSettings {
+        blink_interval: 30,
+        posture_interval: 3600,
+        ..Default::default()
+    }
+}
+
+/// The reminder types of `events`, in order.
+pub fn kinds(events: Vec<ReminderEvent>) -> Vec<ReminderType> {
+    events
+        .into_iter()
+        .map(|ReminderEvent::Due { kind, .. }| kind)
+        .collect()
+}